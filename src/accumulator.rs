@@ -0,0 +1,271 @@
+//! Flat running sums for the week currently being reduced, the spread of the values summed,
+//! and sums of the wind stress of each 6-hourly wind.
+
+use crate::reduce::DailyFields;
+use crate::reduce::Grid;
+use crate::stress::Stress;
+
+/// Sum, and mean, of a cell that never saw a real value; also the output _FillValue.
+pub const FILL: f64 = -999.9;
+
+/// Running sums and counts for one window, one entry per grid cell in [lat][lon] order.
+/// Sums hold FILL until a cell sees a real value.
+pub struct Accumulator {
+    pub uwnd: Vec<f64>,
+    pub vwnd: Vec<f64>,
+    pub ws: Vec<f64>,
+    pub nobs: Vec<f64>,
+    pub count_uwnd: Vec<i32>,
+    pub count_vwnd: Vec<i32>,
+    pub count_ws: Vec<i32>,
+    pub spread_uwnd: Spread,
+    pub spread_vwnd: Spread,
+    pub spread_ws: Spread,
+    /// Sums of eastward and northward stress and its magnitude, from 6-hourly winds with both components.
+    pub taux: Vec<f64>,
+    pub tauy: Vec<f64>,
+    pub tau: Vec<f64>,
+    pub count_tau: Vec<i32>,
+    // 6-hourly steps per cell in each daily field
+    nsteps: usize,
+    // how each 6-hourly wind's stress is computed
+    stress: Stress,
+}
+
+impl Accumulator {
+    /// Empty sums sized for every cell of the grid, computing stress as given.
+    pub fn new(grid: &Grid, stress: &Stress) -> Accumulator {
+        let ncells = grid.ncells();
+        Accumulator {
+            uwnd: vec![FILL; ncells],
+            vwnd: vec![FILL; ncells],
+            ws: vec![FILL; ncells],
+            nobs: vec![FILL; ncells],
+            count_uwnd: vec![0; ncells],
+            count_vwnd: vec![0; ncells],
+            count_ws: vec![0; ncells],
+            spread_uwnd: Spread::new(ncells),
+            spread_vwnd: Spread::new(ncells),
+            spread_ws: Spread::new(ncells),
+            taux: vec![FILL; ncells],
+            tauy: vec![FILL; ncells],
+            tau: vec![FILL; ncells],
+            count_tau: vec![0; ncells],
+            nsteps: grid.nsteps,
+            stress: *stress,
+        }
+    }
+
+    /// Return to the empty state without reallocating, so the buffers can serve the next week.
+    pub fn reset(&mut self) {
+        self.uwnd.fill(FILL);
+        self.vwnd.fill(FILL);
+        self.ws.fill(FILL);
+        self.nobs.fill(FILL);
+        self.count_uwnd.fill(0);
+        self.count_vwnd.fill(0);
+        self.count_ws.fill(0);
+        self.spread_uwnd.reset();
+        self.spread_vwnd.reset();
+        self.spread_ws.reset();
+        self.taux.fill(FILL);
+        self.tauy.fill(FILL);
+        self.tau.fill(FILL);
+        self.count_tau.fill(0);
+    }
+
+    /// Fold one day of upstream values into the sums.
+    pub fn add_daily(&mut self, daily: &DailyFields) {
+        // the spread reads the counts as they stood before this day, so goes first
+        self.spread_uwnd.add(&self.count_uwnd, &daily.uwnd, self.nsteps);
+        self.spread_vwnd.add(&self.count_vwnd, &daily.vwnd, self.nsteps);
+        self.spread_ws.add(&self.count_ws, &daily.ws, self.nsteps);
+        accumulate(&mut self.uwnd, Some(&mut self.count_uwnd), &daily.uwnd, self.nsteps);
+        accumulate(&mut self.vwnd, Some(&mut self.count_vwnd), &daily.vwnd, self.nsteps);
+        accumulate(&mut self.ws, Some(&mut self.count_ws), &daily.ws, self.nsteps);
+        accumulate(&mut self.nobs, None, &daily.nobs, self.nsteps);
+        self.add_stress(&daily.uwnd, &daily.vwnd);
+    }
+
+    fn add_stress(&mut self, uwnd: &[f64], vwnd: &[f64]) {
+        // stress of each 6-hourly wind with both components present, summed as accumulate sums winds
+
+        for (cell, (us, vs)) in uwnd.chunks_exact(self.nsteps).zip(vwnd.chunks_exact(self.nsteps)).enumerate() {
+            for (&u, &v) in us.iter().zip(vs) {
+                if u.is_nan() || v.is_nan() {
+                    continue;
+                }
+                if self.count_tau[cell] == 0 {
+                    self.taux[cell] = 0.0;
+                    self.tauy[cell] = 0.0;
+                    self.tau[cell] = 0.0;
+                }
+                let (taux, tauy) = self.stress.of(u, v);
+                self.taux[cell] += taux;
+                self.tauy[cell] += tauy;
+                self.tau[cell] += taux.hypot(tauy);
+                self.count_tau[cell] += 1;
+            }
+        }
+    }
+}
+
+/// Running variance, by Welford's method, and extremes of one quantity, one entry per grid cell.
+/// The count of values folded in is kept alongside, as the quantity's count in the [`Accumulator`].
+pub struct Spread {
+    /// Running mean, used only to update m2; the reported mean comes from the plain sum.
+    pub mean: Vec<f64>,
+    /// Sum of squared differences from the running mean.
+    pub m2: Vec<f64>,
+    /// Smallest and largest values, infinite until a cell sees a real value.
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+}
+
+impl Spread {
+    /// Empty statistics for ncells cells.
+    pub fn new(ncells: usize) -> Spread {
+        Spread {
+            mean: vec![0.0; ncells],
+            m2: vec![0.0; ncells],
+            min: vec![f64::INFINITY; ncells],
+            max: vec![f64::NEG_INFINITY; ncells],
+        }
+    }
+
+    /// Return to the empty state without reallocating.
+    pub fn reset(&mut self) {
+        self.mean.fill(0.0);
+        self.m2.fill(0.0);
+        self.min.fill(f64::INFINITY);
+        self.max.fill(f64::NEG_INFINITY);
+    }
+
+    /// Fold the non-NaN values of one daily field in, given how many values of each cell were folded in before.
+    pub fn add(&mut self, count: &[i32], values: &[f64], nsteps: usize) {
+        for (cell, steps) in values.chunks_exact(nsteps).enumerate() {
+            let mut n = count[cell] as f64;
+            for &v in steps {
+                if !v.is_nan() {
+                    n += 1.0;
+                    let delta = v - self.mean[cell];
+                    self.mean[cell] += delta / n;
+                    self.m2[cell] += delta * (v - self.mean[cell]);
+                    self.min[cell] = self.min[cell].min(v);
+                    self.max[cell] = self.max[cell].max(v);
+                }
+            }
+        }
+    }
+
+    /// Per-cell population standard deviation, FILL where there were no values.
+    pub fn std(&self, count: &[i32]) -> Vec<f64> {
+        self.m2.iter().zip(count).map(|(&m2, &c)| if c > 0 { (m2 / c as f64).sqrt() } else { FILL }).collect()
+    }
+
+    /// Per-cell minimum, FILL where there were no values.
+    pub fn minimum(&self) -> Vec<f64> {
+        self.min.iter().map(|&x| if x.is_finite() { x } else { FILL }).collect()
+    }
+
+    /// Per-cell maximum, FILL where there were no values.
+    pub fn maximum(&self) -> Vec<f64> {
+        self.max.iter().map(|&x| if x.is_finite() { x } else { FILL }).collect()
+    }
+}
+
+/// Per-cell mean of a running sum, leaving cells that never saw a value at FILL.
+pub fn mean(sum: &[f64], count: &[i32]) -> Vec<f64> {
+    sum.iter().zip(count).map(|(&s, &c)| {
+        if s != FILL {
+            s / (c as f64)
+        } else {
+            s
+        }
+    }).collect()
+}
+
+/// Per-cell speed of the mean wind vector, sqrt(u² + v²), FILL where either component mean is.
+pub fn vector_speed(u: &[f64], v: &[f64]) -> Vec<f64> {
+    u.iter().zip(v).map(|(&u, &v)| if u != FILL && v != FILL { u.hypot(v) } else { FILL }).collect()
+}
+
+/// Per-cell direction of the mean wind vector in the meteorological convention: degrees clockwise from north
+/// of where the wind blows from, in [0, 360). FILL where either component mean is, or the mean vector is zero.
+pub fn direction(u: &[f64], v: &[f64]) -> Vec<f64> {
+    u.iter().zip(v).map(|(&u, &v)| {
+        if u == FILL || v == FILL || (u == 0.0 && v == 0.0) {
+            FILL
+        } else {
+            // rem_euclid can round a tiny negative angle up to 360 itself
+            let degrees = (-u).atan2(-v).to_degrees().rem_euclid(360.0);
+            if degrees < 360.0 { degrees } else { 0.0 }
+        }
+    }).collect()
+}
+
+/// Per-cell steadiness, the vector mean speed over the scalar mean speed: 1 for wind that never turns,
+/// towards 0 for wind that blows every way. FILL where either is, or the scalar mean speed is zero.
+pub fn steadiness(vector: &[f64], scalar: &[f64]) -> Vec<f64> {
+    vector.iter().zip(scalar).map(|(&vs, &s)| if vs != FILL && s != FILL && s > 0.0 { vs / s } else { FILL }).collect()
+}
+
+/// Add the non-NaN values of one daily field into a running sum, optionally counting them.
+/// Values are visited in the same per-cell order as the upstream time axis, so sums are reproducible.
+pub fn accumulate(sum: &mut [f64], mut count: Option<&mut [i32]>, values: &[f64], nsteps: usize) {
+    for (cell, steps) in values.chunks_exact(nsteps).enumerate() {
+        for &v in steps {
+            if !v.is_nan() {
+                if sum[cell] == FILL {
+                    // drop the fill value and start counting real values
+                    sum[cell] = 0.0;
+                }
+                sum[cell] += v;
+                if let Some(c) = count.as_deref_mut() {
+                    c[cell] += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spread_is_stable_far_from_zero() {
+        // the textbook case where sum of squares minus squared sum loses everything: population variance 22.5,
+        // split over two days with a NaN and a cell that never sees a value
+        let grid = Grid { nlat: 1, nlon: 2, nsteps: 3 };
+        let mut acc = Accumulator::new(&grid, &Stress::default());
+        let day = |steps: [f64; 3]| DailyFields {
+            uwnd: vec![steps[0], steps[1], steps[2], f64::NAN, f64::NAN, f64::NAN],
+            vwnd: vec![f64::NAN; 6],
+            ws: vec![f64::NAN; 6],
+            nobs: vec![f64::NAN; 6],
+        };
+        acc.add_daily(&day([1e9 + 4.0, f64::NAN, 1e9 + 7.0]));
+        acc.add_daily(&day([1e9 + 13.0, 1e9 + 16.0, f64::NAN]));
+
+        assert_eq!(acc.count_uwnd, vec![4, 0]);
+        let std = acc.spread_uwnd.std(&acc.count_uwnd);
+        assert!((std[0] - 22.5f64.sqrt()).abs() < 1e-9, "{}", std[0]);
+        assert_eq!(std[1], FILL);
+        assert_eq!(acc.spread_uwnd.minimum(), vec![1e9 + 4.0, FILL]);
+        assert_eq!(acc.spread_uwnd.maximum(), vec![1e9 + 16.0, FILL]);
+
+        acc.reset();
+        assert_eq!(acc.spread_uwnd.std(&acc.count_uwnd), vec![FILL, FILL]);
+    }
+
+    #[test]
+    fn direction_is_where_the_wind_comes_from() {
+        // northerly, easterly, southerly and westerly winds, then calm and missing cells
+        let u = [0.0, -5.0, 0.0, 5.0, 0.0, FILL];
+        let v = [-5.0, 0.0, 5.0, 0.0, 0.0, 1.0];
+        assert_eq!(direction(&u, &v), vec![0.0, 90.0, 180.0, 270.0, FILL, FILL]);
+        assert_eq!(vector_speed(&u, &v), vec![5.0, 5.0, 5.0, 5.0, 0.0, FILL]);
+        assert_eq!(steadiness(&[5.0, 0.0, 1.0], &[10.0, 0.0, FILL]), vec![0.5, FILL, FILL]);
+    }
+}
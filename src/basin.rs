@@ -0,0 +1,57 @@
+//! Ocean basin lookup for grid cells, against the Argovis basin mask.
+
+/// Map a longitude on [0,360] to [-180,180], as required for mongo geo indexing.
+pub fn tidylon(longitude: f64) -> f64{
+    if longitude <= 180.0{
+        longitude
+    }
+    else{
+        longitude-360.0
+    }
+}
+
+/// Basin number of the basin mask cell nearest a point.
+///
+/// `basins` is a [latitude][longitude] variable on the 1 degree grid of the Argovis basin mask,
+/// with cell centers from -77.5 north and -179.5 east; `longitude` is in [-180,180], see [`tidylon`].
+/// Fails if the mask is smaller than that grid, or cannot be read.
+pub fn find_basin(basins: &netcdf::Variable, longitude: f64, latitude: f64) -> Result<i32, netcdf::error::Error> {    
+    let lonplus = (longitude-0.5).ceil()+0.5;
+    let lonminus = (longitude-0.5).floor()+0.5;
+    let latplus = (latitude-0.5).ceil()+0.5;
+    let latminus = (latitude-0.5).floor()+0.5;
+
+    let lonplus_idx = (lonplus - -179.5) as usize;
+    let lonminus_idx = (lonminus - -179.5) as usize;
+    let latplus_idx = (latplus - -77.5) as usize;
+    let latminus_idx = (latminus - -77.5) as usize;
+
+    let corners_idx = [
+        // bottom left corner, clockwise
+        [latminus_idx, lonminus_idx],
+        [latplus_idx, lonminus_idx],
+        [latplus_idx, lonplus_idx],
+        [latminus_idx, lonplus_idx]
+    ];
+
+    let distances = [
+        (f64::powi(longitude-lonminus, 2) + f64::powi(latitude-latminus, 2)).sqrt(),
+        (f64::powi(longitude-lonminus, 2) + f64::powi(latitude-latplus, 2)).sqrt(),
+        (f64::powi(longitude-lonplus, 2) + f64::powi(latitude-latplus, 2)).sqrt(),
+        (f64::powi(longitude-lonplus, 2) + f64::powi(latitude-latminus, 2)).sqrt()
+    ];
+
+    let mut closecorner_idx = corners_idx[0];
+    let mut closedist = distances[0];
+    for i in 1..4 {
+        if distances[i] < closedist{
+            closecorner_idx = corners_idx[i];
+            closedist = distances[i];
+        }
+    }
+
+    match basins.value::<i64,_>(closecorner_idx){
+        Ok(idx) => Ok(idx as i32),
+        Err(e) => Err(format!("no basin at index {:?} for longitude {} latitude {}: {}", closecorner_idx, longitude, latitude, e).into())
+    }
+}
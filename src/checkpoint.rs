@@ -0,0 +1,124 @@
+//! Sidecar record of which lattice weeks are already safely written to an output file.
+
+use std::fs;
+use std::io::Write;
+
+use crate::report::WeekRecord;
+
+/// The weeks of one output file already written, mirrored to a JSON lines file next to it.
+pub struct Checkpoint {
+    path: String,
+    done: Vec<WeekRecord>,
+}
+
+/// The checkpoint lives next to the output file it describes.
+pub fn checkpoint_path(outpath: &str) -> String {
+    format!("{}.checkpoint", outpath)
+}
+
+impl Checkpoint {
+    /// Start a new, empty checkpoint, discarding any previous one.
+    pub fn fresh(path: &str) -> std::io::Result<Checkpoint> {
+        fs::write(path, "")?;
+        Ok(Checkpoint { path: String::from(path), done: Vec::new() })
+    }
+
+    /// Pick up the weeks recorded by an earlier run; one JSON week record per line.
+    /// A run stopped partway through recording a week can leave its last line incomplete; that week is
+    /// dropped, and cut from the file so later records follow on cleanly. Anything unreadable before it is an error.
+    pub fn load(path: &str) -> std::io::Result<Checkpoint> {
+        let text = fs::read_to_string(path)?;
+        let mut done = Vec::new();
+        let mut kept = 0;
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        for (i, line) in lines.iter().enumerate() {
+            let record = if line.ends_with('\n') { serde_json::from_str(line.trim_end()).ok() } else { None };
+            match record {
+                Some(week) => {
+                    done.push(week);
+                    kept += line.len();
+                }
+                None if line.trim().is_empty() => kept += line.len(),
+                None if i + 1 == lines.len() => {
+                    eprintln!("WARNING: dropping incomplete last record of {}", path);
+                    fs::OpenOptions::new().write(true).open(path)?.set_len(kept as u64)?;
+                }
+                None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {} is not a week record", i + 1))),
+            }
+        }
+        Ok(Checkpoint { path: String::from(path), done })
+    }
+
+    /// Whether the week starting at the given lattice date is already written.
+    pub fn is_done(&self, date: &str) -> bool {
+        self.done.iter().any(|w| w.date == date)
+    }
+
+    /// Number of weeks already written.
+    pub fn completed(&self) -> usize {
+        self.done.len()
+    }
+
+    /// What each written week was built from, in the order they were written.
+    pub fn weeks(&self) -> &[WeekRecord] {
+        &self.done
+    }
+
+    /// Mark a week as complete; only call once its data has been flushed to the output file.
+    pub fn record(&mut self, week: WeekRecord) -> std::io::Result<()> {
+        let mut f = fs::OpenOptions::new().append(true).open(&self.path)?;
+        writeln!(f, "{}", serde_json::to_string(&week)?)?;
+        f.sync_all()?;
+        self.done.push(week);
+        Ok(())
+    }
+
+    /// A finished run needs no checkpoint; rerunning it afterwards starts over.
+    pub fn remove(self) -> std::io::Result<()> {
+        fs::remove_file(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week(date: &str) -> WeekRecord {
+        WeekRecord { date: String::from(date), ndays: 7, inputs: Vec::new(), missing: Vec::new() }
+    }
+
+    fn scratch(name: &str) -> String {
+        std::env::temp_dir().join(format!("ccmp_parse_checkpoint_{}_{}", name, std::process::id())).to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn torn_last_record_is_dropped() {
+        let path = scratch("torn");
+        let mut c = Checkpoint::fresh(&path).unwrap();
+        c.record(week("1993-01-03")).unwrap();
+        c.record(week("1993-01-10")).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &text[..text.len() - 10]).unwrap();
+
+        let mut c = Checkpoint::load(&path).unwrap();
+        assert_eq!(c.completed(), 1);
+        assert!(c.is_done("1993-01-03") && !c.is_done("1993-01-10"));
+        // the week is recorded again on a line of its own
+        c.record(week("1993-01-10")).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap().completed(), 2);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn damage_before_the_last_record_is_an_error() {
+        let path = scratch("damaged");
+        let mut c = Checkpoint::fresh(&path).unwrap();
+        c.record(week("1993-01-03")).unwrap();
+        c.record(week("1993-01-10")).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replacen("1993-01-03\"", "1993-01-03", 1)).unwrap();
+
+        assert_eq!(Checkpoint::load(&path).err().map(|e| e.kind()), Some(std::io::ErrorKind::InvalidData));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,479 @@
+// command line interface: one subcommand per job the pods run
+
+use chrono::Datelike;
+use chrono::NaiveDate;
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
+use ccmp_parse::config::RunConfig;
+use ccmp_parse::config::YearRange;
+use ccmp_parse::config::DEFAULT_OUTFILE;
+use ccmp_parse::error::CcmpError;
+use ccmp_parse::lattice;
+use ccmp_parse::lattice::WindowKind;
+use ccmp_parse::stress::Drag;
+use ccmp_parse::writer::Chunking;
+use ccmp_parse::writer::Precision;
+
+#[derive(Parser)]
+#[command(version, about = "Reduce REMSS CCMP 6-hourly winds to OISST-aligned weekly means, and inspect the results")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Average upstream daily files into one reduced netcdf file per year
+    #[command(long_about = "Average upstream daily files into one reduced netcdf file per year.\n\n\
+        Each year's output holds one time step per lattice window that starts in the year: every --window days from --anchor, \
+        or each calendar day, pentad, month or season. \
+        The last window of a year reads into January of the next, so that year's files must be under a --data-root too.\n\n\
+        Beside the uwnd, vwnd and ws means, each output holds their population standard deviation, minimum and maximum (*_std, *_min, *_max), \
+        the mean wind vector's speed, direction and steadiness (vector_ws, wind_dir, steadiness), \
+        and wind stress computed from every 6-hourly wind before averaging (taux, tauy, tau).\n\n\
+        Progress is checkpointed to OUT.checkpoint after every window; rerunning the same command after an interruption resumes from it, \
+        provided the run definition is unchanged apart from --threads. \
+        Each window's input files and day count are summarized in OUT.report.json and the output's ndays variable.")]
+    Reduce(ReduceArgs),
+    /// Check that reduced files cover every window of their lattice, and recompute sampled values from upstream
+    #[command(long_about = "Check that reduced files cover every window of their lattice, and recompute sampled values from upstream.\n\n\
+        Each file's time axis is checked against the lattice, then --samples random (week, cell, variable) values are recomputed \
+        from the upstream files of their window and compared with both the stored mean, or total for nobs, and its *_nobs count. \
+        The same --seed picks the same samples; --full checks every value instead.\n\n\
+        Each --reference-week is also recomputed in full by a simple reference reduction, reporting each variable's largest error, \
+        how many cells disagree, and the --worst cells. Exits non-zero if any file has a problem.")]
+    Verify(VerifyArgs),
+    /// Print the upstream 6-hourly values at one grid cell over a range of days, beside the reduced means
+    #[command(long_about = "Print the upstream 6-hourly values at one grid cell over a range of days, beside the reduced means.\n\n\
+        The cell nearest --lat and --lon is used, with longitudes given either from -180 to 180 or from 0 to 360. \
+        Days are grouped by the window they fall in, each followed by that window's mean and count for the cell \
+        from the reduced file. Upstream files and reduced files are found as for reduce, from --config and the flags given with it.")]
+    Dump(DumpArgs),
+    /// Average reduced files over baseline years into a climatology with one time step per window of the year
+    #[command(long_about = "Average reduced files over baseline years into a climatology with one time step per window of the year.\n\n\
+        The k-th window starting in each baseline year is averaged into the k-th step of the climatology, \
+        with a *_nyears count of the years that had a value at each cell. Every baseline year must already be reduced \
+        with the same --window and --anchor, found at --out. Time bounds follow the CF climatology convention, \
+        from the window's start in the first baseline year to its end in the last.")]
+    Climatology(ClimatologyArgs),
+    /// Subtract a climatology from reduced files, giving each window's anomaly
+    #[command(long_about = "Subtract a climatology from reduced files, giving each window's anomaly.\n\n\
+        Each year's anomaly file has the grid, time axis, counts and metadata of its reduced file, \
+        with uwnd, vwnd and ws replaced by their difference from the same window of the year in the --baseline climatology. \
+        Cells missing from either are left as fill.")]
+    Anomalies(AnomaliesArgs),
+    /// List which upstream files each window needs, and which of them are missing
+    Inventory(InventoryArgs),
+    /// Load reduced files into mongodb as one time series document per grid cell
+    LoadDb(LoadDbArgs),
+}
+
+fn parse_anchor(s: &str) -> Result<String, String> {
+    chrono::DateTime::parse_from_rfc3339(s).map(|_| String::from(s)).map_err(|e| format!("anchor {} is not an RFC 3339 date like {}: {}", s, lattice::OISST_ANCHOR, e))
+}
+
+fn resolve(path: &Option<String>, years: Option<YearRange>, apply: impl FnOnce(&mut RunConfig)) -> Result<(YearRange, RunConfig), CcmpError> {
+    // start from the config file if one is given, or the built-in defaults, let flags override it, and validate the result
+
+    let mut config = match path {
+        Some(p) => RunConfig::load(p)?,
+        None => RunConfig::default(),
+    };
+    if years.is_some() {
+        config.years = years;
+    }
+    apply(&mut config);
+    config.validate()?;
+    let years = config.years.ok_or_else(|| CcmpError::Config(String::from("no years given, on the command line or as years in the config")))?;
+
+    Ok((years, config))
+}
+
+#[derive(Args)]
+pub struct InputArgs {
+    /// Directory holding upstream files as DIR/yYYYY/mMM/CCMP_Wind_Analysis_YYYYMMDD_V03.0_L4.0.nc;
+    /// repeat to search several mounts in order. defaults to /tmp
+    #[arg(long = "data-root", value_name = "DIR")]
+    pub roots: Vec<String>,
+}
+
+impl InputArgs {
+    fn apply(&self, config: &mut RunConfig) {
+        if !self.roots.is_empty() {
+            config.input.roots = self.roots.clone();
+        }
+    }
+}
+
+/// Fixed windows of some number of days, or calendar windows.
+#[derive(Clone)]
+pub enum WindowChoice {
+    Days(i64),
+    Kind(WindowKind),
+}
+
+fn parse_window(s: &str) -> Result<WindowChoice, String> {
+    // a number of days, or a window kind like "monthly"
+
+    match s.parse::<i64>() {
+        Ok(days) if days >= 1 => Ok(WindowChoice::Days(days)),
+        Ok(days) => Err(format!("window of {} days should be at least 1", days)),
+        Err(_) => s.parse().map(WindowChoice::Kind),
+    }
+}
+
+#[derive(Args)]
+pub struct WindowArgs {
+    /// Start of one fixed window; the lattice extends from it in both directions. defaults to the OISST week start 1993-01-03
+    #[arg(long, value_name = "DATE", value_parser = parse_anchor)]
+    pub anchor: Option<String>,
+    /// Days averaged into each fixed window, and the spacing between window starts; or calendar windows:
+    /// daily, pentad (73 a year from January 1, the one holding February 29 six days long), monthly,
+    /// or seasonal (DJF, MAM, JJA, SON, each year holding the DJF starting in its December). defaults to 7
+    #[arg(long, value_name = "DAYS|KIND", value_parser = parse_window)]
+    pub window: Option<WindowChoice>,
+}
+
+impl WindowArgs {
+    fn apply(&self, config: &mut RunConfig) {
+        if let Some(anchor) = &self.anchor {
+            config.window.anchor = anchor.clone();
+        }
+        match &self.window {
+            Some(WindowChoice::Days(days)) => {
+                config.window.kind = WindowKind::Fixed;
+                config.window.days = *days;
+            }
+            Some(WindowChoice::Kind(kind)) => config.window.kind = *kind,
+            None => {}
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ReduceArgs {
+    /// Year to reduce, or an inclusive range like 1993-2022; may be left to the config
+    pub years: Option<YearRange>,
+    /// Run definition in TOML or YAML; flags given alongside it take precedence
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<String>,
+    /// Print the complete run definition, with flags applied, as TOML and exit
+    #[arg(long)]
+    pub print_config: bool,
+    /// Output path, with {year} replaced by each year reduced. defaults to /tmp/ccmp_means_{year}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub out: Option<String>,
+    /// Number of windows reduced at once; memory is roughly 300Mi per thread. defaults to 1
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub threads: Option<u64>,
+    #[command(flatten)]
+    pub input: InputArgs,
+    #[command(flatten)]
+    pub window: WindowArgs,
+    /// Fail, rather than warn, when a window has fewer than its full number of days of input
+    #[arg(long)]
+    pub require_full_weeks: bool,
+    /// Deflate level for the gridded variables; off by default
+    #[arg(long, value_name = "LEVEL", value_parser = clap::value_parser!(i32).range(0..=9))]
+    pub deflate: Option<i32>,
+    /// Byte shuffle the gridded variables before deflating
+    #[arg(long)]
+    pub shuffle: bool,
+    /// Chunk shape of the gridded variables: default, map (one time step per chunk),
+    /// timeseries (a whole year of 32x32 cell patches per chunk, stored uncompressed), or an explicit T,Y,X shape
+    #[arg(long, value_name = "SHAPE")]
+    pub chunking: Option<Chunking>,
+    /// Storage type of the means: f64 (default), f32, or i16 packed at 0.01 m/s; counts are always i16,
+    /// and nobs, wind_dir and stresses are f32 unless f64 is asked for
+    #[arg(long, value_name = "TYPE")]
+    pub precision: Option<Precision>,
+    /// Drag coefficient for wind stress: large-yeager (default; Large & Yeager 2009, as for CORE forcing),
+    /// large-pond (Large & Pond 1981), or a constant like 1.3e-3
+    #[arg(long, value_name = "FORMULATION|CD")]
+    pub drag: Option<Drag>,
+    /// Air density for wind stress, in kg m-3. defaults to 1.22
+    #[arg(long, value_name = "KG_M3")]
+    pub air_density: Option<f64>,
+}
+
+impl ReduceArgs {
+    pub fn resolve(&self) -> Result<(YearRange, RunConfig), CcmpError> {
+        resolve(&self.config, self.years, |config| {
+            self.input.apply(config);
+            self.window.apply(config);
+            let output = &mut config.output;
+            if let Some(out) = &self.out {
+                output.path = out.clone();
+            }
+            if let Some(threads) = self.threads {
+                output.threads = threads as usize;
+            }
+            output.require_full_weeks |= self.require_full_weeks;
+            if self.deflate.is_some() {
+                output.deflate = self.deflate;
+            }
+            output.shuffle |= self.shuffle;
+            if let Some(chunking) = &self.chunking {
+                output.chunking = chunking.clone();
+            }
+            if let Some(precision) = self.precision {
+                output.precision = precision;
+            }
+            if let Some(drag) = self.drag {
+                config.stress.drag = drag;
+            }
+            if let Some(air_density) = self.air_density {
+                config.stress.air_density = air_density;
+            }
+        })
+    }
+}
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// Year of the reduced file to check, or an inclusive range like 1993-2022; may be left to the config
+    pub years: Option<YearRange>,
+    /// Run definition in TOML or YAML that produced the files; flags given alongside it take precedence
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<String>,
+    /// Reduced file path, with {year} replaced by each year checked. defaults to /tmp/ccmp_means_{year}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub out: Option<String>,
+    #[command(flatten)]
+    pub input: InputArgs,
+    #[command(flatten)]
+    pub window: WindowArgs,
+    /// Number of random values per file to recompute from upstream; 0 checks the time axis only
+    #[arg(long, value_name = "N", default_value_t = 1000, conflicts_with = "full")]
+    pub samples: usize,
+    /// Seed for picking samples; the same seed checks the same values
+    #[arg(long, value_name = "SEED", default_value_t = 0)]
+    pub seed: u64,
+    /// Recompute every value of every file rather than a sample
+    #[arg(long)]
+    pub full: bool,
+    /// Start of a window to recompute in full and report errors for, like 1993-12-26T00:00:00.000Z; repeat for several
+    #[arg(long, value_name = "DATE", value_parser = parse_anchor)]
+    pub reference_week: Vec<String>,
+    /// Number of worst cells to list for each variable of a reference week
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    pub worst: usize,
+}
+
+impl VerifyArgs {
+    pub fn resolve(&self) -> Result<(YearRange, RunConfig), CcmpError> {
+        resolve(&self.config, self.years, |config| {
+            self.input.apply(config);
+            self.window.apply(config);
+            if let Some(out) = &self.out {
+                config.output.path = out.clone();
+            }
+        })
+    }
+}
+
+#[derive(Args)]
+pub struct DumpArgs {
+    /// Latitude of the cell, snapped to the nearest grid point
+    #[arg(long, allow_hyphen_values = true)]
+    pub lat: f64,
+    /// Longitude of the cell, from -180 to 180 or 0 to 360, snapped to the nearest grid point
+    #[arg(long, allow_hyphen_values = true)]
+    pub lon: f64,
+    /// First day to print, as YYYY-MM-DD
+    #[arg(long, value_name = "DATE")]
+    pub from: NaiveDate,
+    /// Last day to print, as YYYY-MM-DD. defaults to --from
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<NaiveDate>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DumpFormat::Table)]
+    pub format: DumpFormat,
+    /// Run definition in TOML or YAML locating the upstream and reduced files; flags given alongside it take precedence
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<String>,
+    /// Reduced file path, with {year} replaced by each window's year. defaults to /tmp/ccmp_means_{year}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub out: Option<String>,
+    #[command(flatten)]
+    pub input: InputArgs,
+    #[command(flatten)]
+    pub window: WindowArgs,
+}
+
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum DumpFormat {
+    Table,
+    Json,
+}
+
+impl DumpArgs {
+    pub fn resolve(&self) -> Result<RunConfig, CcmpError> {
+        // the years come from --from and --to rather than the command line or config
+        let to = self.to.unwrap_or(self.from);
+        if to < self.from {
+            return Err(CcmpError::Config(format!("--to {} is before --from {}", to, self.from)));
+        }
+        let years = YearRange { first: self.from.year(), last: to.year() };
+        let (_, config) = resolve(&self.config, Some(years), |config| {
+            self.input.apply(config);
+            self.window.apply(config);
+            if let Some(out) = &self.out {
+                config.output.path = out.clone();
+            }
+        })?;
+
+        Ok(config)
+    }
+}
+
+#[derive(Args)]
+pub struct ClimatologyArgs {
+    /// Inclusive range of baseline years, like 1993-2020; may be left to the config, and defaults to 1993-2020
+    pub baseline: Option<YearRange>,
+    /// Run definition in TOML or YAML that produced the reduced files; flags given alongside it take precedence
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<String>,
+    /// Reduced file path, with {year} replaced by each baseline year. defaults to /tmp/ccmp_means_{year}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub out: Option<String>,
+    /// Climatology path, with {baseline} replaced by the baseline years. defaults to /tmp/ccmp_climatology_{baseline}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub climatology: Option<String>,
+    #[command(flatten)]
+    pub window: WindowArgs,
+}
+
+impl ClimatologyArgs {
+    pub fn resolve(&self) -> Result<RunConfig, CcmpError> {
+        // the years read are the baseline's
+        let (_, config) = resolve(&self.config, None, |config| {
+            self.window.apply(config);
+            if let Some(out) = &self.out {
+                config.output.path = out.clone();
+            }
+            if let Some(baseline) = self.baseline {
+                config.climatology.baseline = baseline;
+            }
+            if let Some(path) = &self.climatology {
+                config.climatology.path = path.clone();
+            }
+            config.years = Some(config.climatology.baseline);
+        })?;
+
+        Ok(config)
+    }
+}
+
+#[derive(Args)]
+pub struct AnomaliesArgs {
+    /// Year of the reduced file to take anomalies of, or an inclusive range like 1993-2022; may be left to the config
+    pub years: Option<YearRange>,
+    /// Run definition in TOML or YAML that produced the reduced files; flags given alongside it take precedence
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<String>,
+    /// Reduced file path, with {year} replaced by each year. defaults to /tmp/ccmp_means_{year}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub out: Option<String>,
+    /// Baseline years of the climatology to subtract, like 1993-2020; may be left to the config, and defaults to 1993-2020
+    #[arg(long, value_name = "YEARS")]
+    pub baseline: Option<YearRange>,
+    /// Climatology path, with {baseline} replaced by the baseline years. defaults to /tmp/ccmp_climatology_{baseline}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub climatology: Option<String>,
+    /// Anomaly output path, with {year} replaced by each year. defaults to /tmp/ccmp_anomalies_{year}.nc
+    #[arg(long, value_name = "TEMPLATE")]
+    pub anomalies: Option<String>,
+    #[command(flatten)]
+    pub window: WindowArgs,
+}
+
+impl AnomaliesArgs {
+    pub fn resolve(&self) -> Result<(YearRange, RunConfig), CcmpError> {
+        resolve(&self.config, self.years, |config| {
+            self.window.apply(config);
+            if let Some(out) = &self.out {
+                config.output.path = out.clone();
+            }
+            if let Some(baseline) = self.baseline {
+                config.climatology.baseline = baseline;
+            }
+            if let Some(path) = &self.climatology {
+                config.climatology.path = path.clone();
+            }
+            if let Some(anomalies) = &self.anomalies {
+                config.climatology.anomalies = anomalies.clone();
+            }
+        })
+    }
+}
+
+#[derive(Args)]
+pub struct InventoryArgs {
+    /// Year to take stock of, or an inclusive range like 1993-2022; may be left to the config
+    pub years: Option<YearRange>,
+    /// Run definition in TOML or YAML to take stock for; flags given alongside it take precedence
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<String>,
+    #[command(flatten)]
+    pub input: InputArgs,
+    #[command(flatten)]
+    pub window: WindowArgs,
+    /// List every window, not just those with missing files
+    #[arg(long)]
+    pub all: bool,
+}
+
+impl InventoryArgs {
+    pub fn resolve(&self) -> Result<(YearRange, RunConfig), CcmpError> {
+        resolve(&self.config, self.years, |config| {
+            self.input.apply(config);
+            self.window.apply(config);
+        })
+    }
+}
+
+#[derive(Args)]
+pub struct LoadDbArgs {
+    /// Year of the reduced files to load, or an inclusive range like 1993-2022
+    pub years: YearRange,
+    /// Reduced file path, with {year} replaced by each year loaded
+    #[arg(long, value_name = "TEMPLATE", default_value = DEFAULT_OUTFILE)]
+    pub out: String,
+    /// Basin mask on a 1 degree grid, used to tag each cell with its ocean basin
+    #[arg(long, value_name = "FILE", default_value = "/tmp/basinmask_01.nc")]
+    pub basins: String,
+    /// Variable of the basin mask holding basin numbers
+    #[arg(long, value_name = "NAME", default_value = "BASIN_TAG")]
+    pub basin_var: String,
+    /// Connection string of the database to load
+    #[arg(long, env = "MONGODB_URI", value_name = "URI", hide_env_values = true)]
+    pub mongodb_uri: String,
+    /// Database name
+    #[arg(long, default_value = "argo")]
+    pub database: String,
+    /// Collection receiving one document per grid cell, plus a metadata document
+    #[arg(long, default_value = "ccmpwind")]
+    pub collection: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anomalies_baseline_is_never_taken_for_years() {
+        let baseline = YearRange { first: 1993, last: 2020 };
+        let parse = |args: &[&str]| match Cli::try_parse_from(args).map(|cli| cli.command) {
+            Ok(Command::Anomalies(a)) => Ok((a.years, a.baseline)),
+            Ok(_) => panic!("parsed as another subcommand"),
+            Err(e) => Err(e.kind()),
+        };
+
+        // the years left to the config, with only the baseline given
+        assert_eq!(parse(&["ccmp_parse", "anomalies", "--baseline", "1993-2020", "--config", "run.toml"]), Ok((None, Some(baseline))));
+        assert_eq!(parse(&["ccmp_parse", "anomalies", "2017", "--baseline", "1993-2020"]), Ok((Some(YearRange { first: 2017, last: 2017 }), Some(baseline))));
+        assert_eq!(parse(&["ccmp_parse", "anomalies", "2017", "1993-2020"]), Err(clap::error::ErrorKind::UnknownArgument));
+    }
+}
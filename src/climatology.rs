@@ -0,0 +1,172 @@
+//! Multi-year climatologies of reduced files, with one time step per window of the year,
+//! and anomaly files giving each window's departure from its climatology.
+//!
+//! Windows are matched by their position in the year: the k-th window starting in each year
+//! feeds the k-th step of the climatology, whether that is a lattice week, pentad, month or season.
+
+use crate::accumulator::FILL;
+use crate::config::outfile_path;
+use crate::config::RunConfig;
+use crate::error::CcmpError;
+use crate::lattice;
+use crate::lattice::WindowKind;
+use crate::run::nowstring;
+use crate::writer;
+use crate::writer::Unpacking;
+use crate::writer::QUANTITIES;
+
+/// Average each window of the year over the reduced files of the configured baseline years,
+/// into the configured climatology file, and return its path. Every baseline year's file must exist.
+pub fn build_climatology(config: &RunConfig) -> Result<String, CcmpError> {
+    let baseline = config.climatology.baseline;
+    let mut reduced = Vec::new();
+    let mut lattices = Vec::new();
+    for year in baseline.years() {
+        reduced.push(open_reduced(config, year)?);
+        lattices.push(config.window.lattice_in_year(year)?);
+    }
+    let (firstpath, first) = &reduced[0];
+    let shape = grid_shape(firstpath, first)?;
+    for (path, f) in reduced.iter() {
+        if grid_shape(path, f)? != shape {
+            return Err(CcmpError::schema(path, format!("grid differs from the {:?} (latitude, longitude) of {}", shape, firstpath)));
+        }
+    }
+
+    // each step runs from its window's start in the first year that has it to its end in the last
+    let nslots = lattices.iter().map(|l| l.len()).max().unwrap_or(0);
+    let mut bounds = Vec::new();
+    for slot in 0..nslots {
+        let earliest = lattices.iter().find_map(|l| l.get(slot)).ok_or_else(|| CcmpError::Config(format!("no baseline year has a window {}", slot)))?;
+        let latest = lattices.iter().rev().find_map(|l| l.get(slot)).ok_or_else(|| CcmpError::Config(format!("no baseline year has a window {}", slot)))?;
+        let start = lattice::days_since_epoch(&[earliest.clone(), latest.clone()])?;
+        bounds.push([start[0], start[1] + config.window.window_days(latest)?.len() as i64]);
+    }
+
+    let layout = config.output.layout();
+    let outpath = config.climatology.climatology_path();
+    let mut outfile = writer::create_climatology(&outpath, first, &bounds, &config.window, &baseline.to_string(), &nowstring(), &layout).map_err(|e| CcmpError::output(&outpath, e))?;
+    writer::write_run_config(&mut outfile, &config.to_toml()).map_err(|e| CcmpError::output(&outpath, e))?;
+
+    let ncells = shape.0*shape.1;
+    for slot in 0..nslots {
+        let mut sums = vec![vec![0.0; ncells]; QUANTITIES.len()];
+        let mut nyears = vec![vec![0; ncells]; QUANTITIES.len()];
+        for ((path, f), timelattice) in reduced.iter().zip(lattices.iter()) {
+            if slot >= timelattice.len() {
+                continue;
+            }
+            for (q, name) in QUANTITIES.iter().enumerate() {
+                for (cell, x) in read_field(path, f, name, slot)?.into_iter().enumerate() {
+                    if let Some(x) = x {
+                        sums[q][cell] += x;
+                        nyears[q][cell] += 1;
+                    }
+                }
+            }
+        }
+        let means: Vec<Vec<f64>> = sums.iter().zip(nyears.iter()).map(|(s, n)| {
+            s.iter().zip(n.iter()).map(|(&s, &n)| if n > 0 { s / n as f64 } else { FILL }).collect()
+        }).collect();
+        writer::write_climatology(&mut outfile, slot, &means, &nyears, &layout).map_err(|e| CcmpError::output(&outpath, e))?;
+    }
+    println!("wrote {} averaging {} windows over {}", outpath, config.window.describe(), baseline);
+
+    Ok(outpath)
+}
+
+/// Subtract the configured climatology from one year's reduced file, window by window, into the
+/// configured anomaly file, and return its path. Windows the climatology has no value for are left as fill.
+pub fn write_anomalies(year: i32, config: &RunConfig) -> Result<String, CcmpError> {
+    let (reducedpath, reduced) = open_reduced(config, year)?;
+    let climatologypath = config.climatology.climatology_path();
+    let climatology = netcdf::open(&climatologypath).map_err(|e| CcmpError::input(&climatologypath, e))?;
+    check_window_kind(&climatologypath, &climatology, config.window.kind)?;
+    check_windows_line_up(&climatologypath, &climatology, config)?;
+    let shape = grid_shape(&reducedpath, &reduced)?;
+    if grid_shape(&climatologypath, &climatology)? != shape {
+        return Err(CcmpError::schema(&climatologypath, format!("grid differs from the {:?} (latitude, longitude) of {}", shape, reducedpath)));
+    }
+    let nslots = climatology.variable("time").ok_or_else(|| CcmpError::schema(&climatologypath, "no time variable"))?.len();
+
+    let layout = config.output.layout();
+    let outpath = outfile_path(&config.climatology.anomalies, year);
+    let baseline = config.climatology.baseline.to_string();
+    let mut outfile = writer::create_anomalies(&outpath, &reduced, &reducedpath, &climatologypath, &baseline, &nowstring(), &layout).map_err(|e| CcmpError::output(&outpath, e))?;
+
+    let ndays = reduced.variable("ndays").ok_or_else(|| CcmpError::schema(&reducedpath, "no ndays variable"))?.values::<i32, _>(..).map_err(|e| CcmpError::input(&reducedpath, e))?;
+    for (time, &ndays) in ndays.iter().enumerate() {
+        let mut anomalies = Vec::new();
+        let mut counts = Vec::new();
+        for name in &QUANTITIES[..3] {
+            let means = read_field(&reducedpath, &reduced, name, time)?;
+            let normals = if time < nslots {
+                read_field(&climatologypath, &climatology, name, time)?
+            } else {
+                vec![None; means.len()]
+            };
+            anomalies.push(means.iter().zip(normals.iter()).map(|(m, c)| match (m, c) {
+                (Some(m), Some(c)) => m - c,
+                _ => FILL,
+            }).collect());
+            counts.push(read_field(&reducedpath, &reduced, &format!("{}_nobs", name), time)?.iter().map(|n| n.unwrap_or(0.0) as i32).collect());
+        }
+        writer::write_anomalies(&mut outfile, time, &anomalies, &counts, ndays, &layout).map_err(|e| CcmpError::output(&outpath, e))?;
+    }
+    println!("wrote {} from {} and {}", outpath, reducedpath, climatologypath);
+
+    Ok(outpath)
+}
+
+fn open_reduced(config: &RunConfig, year: i32) -> Result<(String, netcdf::File), CcmpError> {
+    // a year's reduced file, once its windows are known to be the configured ones
+
+    let path = outfile_path(&config.output.path, year);
+    let f = netcdf::open(&path).map_err(|e| CcmpError::input(&path, e))?;
+    check_window_kind(&path, &f, config.window.kind)?;
+    let time = f.variable("time").ok_or_else(|| CcmpError::schema(&path, "no time variable"))?.values::<i64, _>(..).map_err(|e| CcmpError::input(&path, e))?;
+    if time != lattice::days_since_epoch(&config.window.lattice_in_year(year)?)? {
+        return Err(CcmpError::schema(&path, format!("time coordinate does not match the configured {} windows of {}", config.window.describe(), year)));
+    }
+
+    Ok((path, f))
+}
+
+fn check_window_kind(path: &str, f: &netcdf::File, kind: WindowKind) -> Result<(), CcmpError> {
+    let recorded = writer::window_kind(f);
+    if recorded != kind.to_string() {
+        return Err(CcmpError::schema(path, format!("holds {} windows, but {} windows are configured", recorded, kind)));
+    }
+
+    Ok(())
+}
+
+fn check_windows_line_up(path: &str, f: &netcdf::File, config: &RunConfig) -> Result<(), CcmpError> {
+    // fixed windows of the same kind can still start on other days, or run for other lengths,
+    // so compare the windows the climatology was built with over its first baseline year
+
+    let recorded = writer::read_run_config(f).and_then(|s| toml::from_str::<RunConfig>(&s).ok())
+        .ok_or_else(|| CcmpError::schema(path, "no readable run_config attribute recording the windows it was built with"))?;
+    let year = recorded.climatology.baseline.first;
+    if recorded.window.describe() != config.window.describe() || recorded.window.lattice_in_year(year)? != config.window.lattice_in_year(year)? {
+        return Err(CcmpError::schema(path, format!("was built with {} windows anchored at {}, which don't line up with the configured {} windows anchored at {}",
+            recorded.window.describe(), recorded.window.anchor, config.window.describe(), config.window.anchor)));
+    }
+
+    Ok(())
+}
+
+fn grid_shape(path: &str, f: &netcdf::File) -> Result<(usize, usize), CcmpError> {
+    let len = |name: &str| f.variable(name).map(|v| v.len()).ok_or_else(|| CcmpError::schema(path, format!("no {} variable", name)));
+    Ok((len("latitude")?, len("longitude")?))
+}
+
+fn read_field(path: &str, f: &netcdf::File, name: &str, time: usize) -> Result<Vec<Option<f64>>, CcmpError> {
+    // one time step of a gridded variable, unpacked, with None for fill
+
+    let v = f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("no {} variable", name)))?;
+    let unpacking = Unpacking::of(&v);
+    let raw = v.values::<f64, _>((time, .., ..)).map_err(|e| CcmpError::input(path, e))?;
+
+    Ok(raw.into_iter().map(|x| unpacking.unpack(x)).collect())
+}
@@ -0,0 +1,336 @@
+//! Complete run definitions, read from TOML or YAML files and overridden by command line flags.
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+
+use crate::error::CcmpError;
+use crate::lattice;
+use crate::lattice::WindowKind;
+use crate::reduce::Grid;
+use crate::reduce::Variables;
+use crate::stress::Stress;
+use crate::writer::Chunking;
+use crate::writer::Layout;
+use crate::writer::Precision;
+
+/// Where reduced files land unless configured otherwise.
+pub const DEFAULT_OUTFILE: &str = "/tmp/ccmp_means_{year}.nc";
+/// Where a climatology lands unless configured otherwise.
+pub const DEFAULT_CLIMATOLOGY: &str = "/tmp/ccmp_climatology_{baseline}.nc";
+/// Where anomaly files land unless configured otherwise.
+pub const DEFAULT_ANOMALIES: &str = "/tmp/ccmp_anomalies_{year}.nc";
+/// Where upstream files are found unless configured otherwise.
+pub const DEFAULT_DATA_ROOT: &str = "/tmp";
+/// Upstream files below each data root, as named by REMSS for CCMP V3.0.
+pub const DEFAULT_FILENAME: &str = "y{year}/m{month}/CCMP_Wind_Analysis_{date}_V03.0_L4.0.nc";
+
+/// An inclusive range of years, written "2017" or "1993-2022".
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct YearRange {
+    pub first: i32,
+    pub last: i32,
+}
+
+impl YearRange {
+    /// Every year in the range, in order.
+    pub fn years(&self) -> std::ops::RangeInclusive<i32> {
+        self.first..=self.last
+    }
+}
+
+impl std::str::FromStr for YearRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<YearRange, String> {
+        // a single year like "2017", or an inclusive range like "1993-2022"
+
+        let parse = |y: &str| y.trim().parse::<i32>().map_err(|_| format!("{} is not a year; expected YYYY or YYYY-YYYY", s));
+        let (first, last) = match s.split_once('-') {
+            Some((a, b)) => (parse(a)?, parse(b)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if first > last {
+            return Err(format!("year range {} runs backwards", s));
+        }
+
+        Ok(YearRange { first, last })
+    }
+}
+
+impl std::fmt::Display for YearRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+// config files give years as the same "YYYY" or "YYYY-YYYY" strings as the command line
+impl TryFrom<String> for YearRange {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<YearRange, String> {
+        s.parse()
+    }
+}
+
+impl From<YearRange> for String {
+    fn from(y: YearRange) -> String {
+        y.to_string()
+    }
+}
+
+/// Fill in the {year} placeholder of an output filename template.
+pub fn outfile_path(template: &str, year: i32) -> String {
+    template.replace("{year}", &year.to_string())
+}
+
+/// Refuse a template without {year} for several years, which would all land on the same file.
+pub fn check_template(template: &str, years: YearRange) -> Result<(), String> {
+    if years.first != years.last && !template.contains("{year}") {
+        return Err(format!("output {} needs a {{year}} placeholder to hold years {} to {}", template, years.first, years.last));
+    }
+
+    Ok(())
+}
+
+/// Everything needed to reproduce a reduction; every section and key may be left out to take its default.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunConfig {
+    /// Years to process; may instead be given on the command line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub years: Option<YearRange>,
+    pub input: InputConfig,
+    pub grid: Grid,
+    pub variables: Variables,
+    pub window: WindowConfig,
+    pub output: OutputConfig,
+    pub climatology: ClimatologyConfig,
+    pub stress: Stress,
+}
+
+/// Where upstream daily files are found.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputConfig {
+    /// Directories searched in order for each upstream file.
+    pub roots: Vec<String>,
+    /// Path of an upstream file below a root, with {year}, {month}, {day} and {date} (yyyymmdd) filled in.
+    pub filename: String,
+}
+
+impl Default for InputConfig {
+    fn default() -> InputConfig {
+        InputConfig {
+            roots: vec![String::from(DEFAULT_DATA_ROOT)],
+            filename: String::from(DEFAULT_FILENAME),
+        }
+    }
+}
+
+/// The lattice of averaging windows: calendar days, pentads, months or seasons,
+/// or fixed windows given by the start of one window and the days in each.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    pub kind: WindowKind,
+    /// Start of one fixed window; unused by calendar kinds.
+    pub anchor: String,
+    /// Days in each fixed window; unused by calendar kinds.
+    pub days: i64,
+}
+
+impl Default for WindowConfig {
+    fn default() -> WindowConfig {
+        WindowConfig {
+            kind: WindowKind::Fixed,
+            anchor: String::from(lattice::OISST_ANCHOR),
+            days: lattice::OISST_PERIOD,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Window starts falling in [start, end), as RFC 3339 dates.
+    pub fn lattice(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>, CcmpError> {
+        match self.kind {
+            WindowKind::Fixed => lattice::lattice(&self.anchor, self.days, start, end),
+            kind => lattice::calendar_lattice(kind, start, end),
+        }
+    }
+
+    /// Window starts falling in the given calendar year; the last window may run into the next.
+    pub fn lattice_in_year(&self, year: i32) -> Result<Vec<String>, CcmpError> {
+        let (start, end) = lattice::year_bounds(year)?;
+        self.lattice(start, end)
+    }
+
+    /// The days averaged into the window beginning at start, in the format yyyymmdd.
+    pub fn window_days(&self, start: &str) -> Result<Vec<String>, CcmpError> {
+        match self.kind {
+            WindowKind::Fixed => lattice::timewindow(start, self.days),
+            kind => lattice::calendar_window(kind, start),
+        }
+    }
+
+    /// The longest window, in days.
+    pub fn max_days(&self) -> i64 {
+        self.kind.max_days(self.days)
+    }
+
+    /// The windows in a few words, like "7-day" or "monthly".
+    pub fn describe(&self) -> String {
+        match self.kind {
+            WindowKind::Fixed => format!("{}-day", self.days),
+            kind => kind.to_string(),
+        }
+    }
+}
+
+/// Where and how reduced files are written.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Output path, with {year} filled in.
+    pub path: String,
+    pub threads: usize,
+    pub require_full_weeks: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deflate: Option<i32>,
+    pub shuffle: bool,
+    pub chunking: Chunking,
+    pub precision: Precision,
+}
+
+impl Default for OutputConfig {
+    fn default() -> OutputConfig {
+        OutputConfig {
+            path: String::from(DEFAULT_OUTFILE),
+            threads: 1,
+            require_full_weeks: false,
+            deflate: None,
+            shuffle: false,
+            chunking: Chunking::Default,
+            precision: Precision::F64,
+        }
+    }
+}
+
+/// The baseline years of a climatology built from reduced files, and where it and anomalies from it are written.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClimatologyConfig {
+    pub baseline: YearRange,
+    /// Climatology path, with {baseline} filled in as the baseline years, like 1993-2020.
+    pub path: String,
+    /// Anomaly output path, with {year} filled in.
+    pub anomalies: String,
+}
+
+impl Default for ClimatologyConfig {
+    fn default() -> ClimatologyConfig {
+        ClimatologyConfig {
+            baseline: YearRange { first: 1993, last: 2020 },
+            path: String::from(DEFAULT_CLIMATOLOGY),
+            anomalies: String::from(DEFAULT_ANOMALIES),
+        }
+    }
+}
+
+impl ClimatologyConfig {
+    /// The climatology path, with its baseline filled in.
+    pub fn climatology_path(&self) -> String {
+        self.path.replace("{baseline}", &self.baseline.to_string())
+    }
+}
+
+impl OutputConfig {
+    /// Storage options for the gridded output variables.
+    pub fn layout(&self) -> Layout {
+        Layout { deflate: self.deflate, shuffle: self.shuffle, chunking: self.chunking.clone(), precision: self.precision }
+    }
+}
+
+impl RunConfig {
+    /// Parse a run definition, choosing the format by extension; keys left out take their defaults.
+    pub fn load(path: &str) -> Result<RunConfig, CcmpError> {
+        let text = fs::read_to_string(path).map_err(|e| CcmpError::Config(format!("reading {}: {}", path, e)))?;
+        let config: RunConfig = if path.ends_with(".toml") {
+            toml::from_str(&text).map_err(|e| CcmpError::Config(format!("{}: {}", path, e)))?
+        } else if path.ends_with(".yaml") || path.ends_with(".yml") {
+            serde_yaml::from_str(&text).map_err(|e| CcmpError::Config(format!("{}: {}", path, e)))?
+        } else {
+            return Err(CcmpError::Config(format!("{} should end in .toml, .yaml or .yml", path)));
+        };
+
+        Ok(config)
+    }
+
+    /// Catch definitions that could only fail, or silently do the wrong thing, hours into a run.
+    pub fn validate(&self) -> Result<(), CcmpError> {
+        self.check().map_err(CcmpError::Config)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        // the first problem found with the definition, if any
+
+        if self.input.roots.is_empty() {
+            return Err(String::from("input.roots lists no data roots"));
+        }
+        let f = &self.input.filename;
+        let dated = f.contains("{date}") || (f.contains("{year}") && f.contains("{month}") && f.contains("{day}"));
+        if !dated {
+            return Err(format!("input.filename {} needs {{date}}, or all of {{year}}, {{month}} and {{day}}", f));
+        }
+        if self.grid.nlat == 0 || self.grid.nlon == 0 || self.grid.nsteps == 0 {
+            return Err(format!("grid {:?} has an empty dimension", self.grid));
+        }
+        let names = self.variables.names();
+        if names.iter().any(|n| n.is_empty()) {
+            return Err(format!("variables {:?} includes an empty name", names));
+        }
+        chrono::DateTime::parse_from_rfc3339(&self.window.anchor).map_err(|e| format!("window.anchor {} is not an RFC 3339 date like {}: {}", self.window.anchor, lattice::OISST_ANCHOR, e))?;
+        if self.window.days < 1 {
+            return Err(format!("window.days is {}, but must be at least 1", self.window.days));
+        }
+        let most = self.window.max_days()*self.grid.nsteps as i64;
+        if most > i16::MAX as i64 {
+            return Err(format!("windows of up to {} days of {} steps hold up to {} values per cell, more than the i16 *_nobs counts can hold",
+                self.window.max_days(), self.grid.nsteps, most));
+        }
+        if !(self.stress.air_density > 0.0 && self.stress.air_density.is_finite()) {
+            return Err(format!("stress.air_density is {}, but must be a positive density in kg m-3", self.stress.air_density));
+        }
+        if self.output.threads < 1 {
+            return Err(String::from("output.threads must be at least 1"));
+        }
+        if let Some(level) = self.output.deflate {
+            if !(0..=9).contains(&level) {
+                return Err(format!("output.deflate is {}, but takes a level from 0 to 9", level));
+            }
+        }
+        // windows are written one at a time, and a filtered chunk holding every window would be
+        // read back, decompressed and recompressed whole for each
+        if self.output.chunking == Chunking::TimeSeries && (self.output.deflate.is_some() || self.output.shuffle) {
+            return Err(String::from("output.chunking timeseries can't be combined with deflate or shuffle, \
+                since every window written would recompress every chunk; compress the finished file with nccopy instead"));
+        }
+        if let Some(years) = self.years {
+            check_template(&self.output.path, years)?;
+            check_template(&self.climatology.anomalies, years)?;
+        }
+
+        Ok(())
+    }
+
+    /// The definition as TOML, in the form `load` reads back.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("run config is always representable as TOML")
+    }
+}
@@ -0,0 +1,236 @@
+// point inspection: the upstream 6-hourly values at one grid cell over a range of days,
+// beside the window means and counts reduced from them
+
+use chrono::Datelike;
+use chrono::Duration;
+use serde::Serialize;
+use std::path::Path;
+
+use ccmp_parse::config::outfile_path;
+use ccmp_parse::config::RunConfig;
+use ccmp_parse::error::CcmpError;
+use ccmp_parse::inputs;
+use ccmp_parse::lattice;
+use ccmp_parse::writer::Unpacking;
+use ccmp_parse::writer::QUANTITIES;
+
+use crate::cli::DumpArgs;
+use crate::cli::DumpFormat;
+
+#[derive(Serialize)]
+struct Dump {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    windows: Vec<Window>,
+}
+
+#[derive(Serialize)]
+struct Window {
+    start: String,
+    days: Vec<Day>,
+    // None when the window's reduced file does not exist yet
+    reduced: Option<Means>,
+}
+
+#[derive(Serialize)]
+struct Day {
+    date: String,
+    file: String,
+    // None when the upstream file is missing
+    steps: Option<Vec<Step>>,
+}
+
+#[derive(Serialize)]
+struct Step {
+    hour: usize,
+    uwnd: Option<f64>,
+    vwnd: Option<f64>,
+    ws: Option<f64>,
+    nobs: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct Means {
+    file: String,
+    uwnd: Option<f64>,
+    uwnd_nobs: Option<f64>,
+    vwnd: Option<f64>,
+    vwnd_nobs: Option<f64>,
+    ws: Option<f64>,
+    ws_nobs: Option<f64>,
+    nobs: Option<f64>,
+}
+
+pub fn nearest(axis: &[f64], x: f64) -> usize {
+    // index of the axis value closest to x
+
+    let mut best = 0;
+    for (i, a) in axis.iter().enumerate() {
+        if (a - x).abs() < (axis[best] - x).abs() {
+            best = i;
+        }
+    }
+
+    best
+}
+
+pub fn nearest_longitude(axis: &[f64], x: f64) -> usize {
+    // index of the axis value closest to x around the globe, so -180 to 180 and 0 to 360 longitudes both find their cell
+
+    let distance = |a: f64| {
+        let d = (a - x).rem_euclid(360.0);
+        d.min(360.0 - d)
+    };
+    let mut best = 0;
+    for (i, &a) in axis.iter().enumerate() {
+        if distance(a) < distance(axis[best]) {
+            best = i;
+        }
+    }
+
+    best
+}
+
+fn snap(path: &str, f: &netcdf::File, lat: f64, lon: f64) -> Result<(usize, usize, f64, f64), CcmpError> {
+    // indices and coordinates of the cell of this file nearest lat/lon
+
+    let coordinate = |name: &str| {
+        f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("no {} variable", name)))?
+            .values::<f64, _>(..).map_err(|e| CcmpError::input(path, e))
+    };
+    let latitude = coordinate("latitude")?;
+    let longitude = coordinate("longitude")?;
+    if latitude.is_empty() || longitude.is_empty() {
+        return Err(CcmpError::schema(path, "empty latitude or longitude coordinate"));
+    }
+    let latidx = nearest(&latitude, lat);
+    let lonidx = nearest_longitude(&longitude, lon);
+
+    Ok((latidx, lonidx, latitude[latidx], longitude[lonidx]))
+}
+
+fn present(x: f64) -> Option<f64> {
+    // upstream values are NaN where there is no analysis
+    if x.is_nan() { None } else { Some(x) }
+}
+
+fn upstream_steps(path: &str, args: &DumpArgs, config: &RunConfig, cell: &mut Option<(f64, f64)>) -> Result<Option<Vec<Step>>, CcmpError> {
+    // the 6-hourly values of one upstream file at the cell, or None if the file is missing
+
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let f = netcdf::open(path).map_err(|e| CcmpError::input(path, e))?;
+    let (latidx, lonidx, latitude, longitude) = snap(path, &f, args.lat, args.lon)?;
+    cell.get_or_insert((latitude, longitude));
+    let mut series = Vec::new();
+    for name in config.variables.names() {
+        let v = f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("variable {} not found", name)))?;
+        series.push(v.values::<f64, _>((latidx, lonidx, ..)).map_err(|e| CcmpError::input(path, e))?);
+    }
+    let hours = 24 / config.grid.nsteps.max(1);
+
+    Ok(Some((0..series[0].len()).map(|s| {
+        let at = |q: usize| series[q].get(s).copied().and_then(present);
+        Step { hour: s*hours, uwnd: at(0), vwnd: at(1), ws: at(2), nobs: at(3) }
+    }).collect()))
+}
+
+fn reduced_means(start: &str, args: &DumpArgs, config: &RunConfig, cell: &mut Option<(f64, f64)>) -> Result<Option<Means>, CcmpError> {
+    // the means and counts stored for the window beginning at start, or None if its reduced file does not exist
+
+    let year = lattice::parse_date(start)?.year();
+    let path = outfile_path(&config.output.path, year);
+    if !Path::new(&path).exists() {
+        return Ok(None);
+    }
+    let week = config.window.lattice_in_year(year)?.iter().position(|d| d == start)
+        .ok_or_else(|| CcmpError::Config(format!("{} is not a window start of the configured lattice", start)))?;
+    let f = netcdf::open(&path).map_err(|e| CcmpError::input(&path, e))?;
+    let (latidx, lonidx, latitude, longitude) = snap(&path, &f, args.lat, args.lon)?;
+    cell.get_or_insert((latitude, longitude));
+    let stored = |name: &str| -> Result<Option<f64>, CcmpError> {
+        let v = f.variable(name).ok_or_else(|| CcmpError::schema(&path, format!("no {} variable", name)))?;
+        let x = v.value::<f64, _>([week, latidx, lonidx]).map_err(|e| CcmpError::input(&path, e))?;
+        Ok(Unpacking::of(&v).unpack(x))
+    };
+    let [uwnd, vwnd, ws, nobs] = QUANTITIES;
+
+    Ok(Some(Means {
+        uwnd: stored(uwnd)?,
+        uwnd_nobs: stored(&format!("{}_nobs", uwnd))?,
+        vwnd: stored(vwnd)?,
+        vwnd_nobs: stored(&format!("{}_nobs", vwnd))?,
+        ws: stored(ws)?,
+        ws_nobs: stored(&format!("{}_nobs", ws))?,
+        nobs: stored(nobs)?,
+        file: path,
+    }))
+}
+
+pub fn run(args: &DumpArgs) -> Result<(), CcmpError> {
+    // gather each window overlapping --from to --to, with its days in the range and its reduced values, then print them
+
+    let config = args.resolve()?;
+    let to = args.to.unwrap_or(args.from);
+    let (first, last) = (args.from.format("%Y%m%d").to_string(), to.format("%Y%m%d").to_string());
+    let starts = config.window.lattice(args.from - Duration::days(config.window.max_days() - 1), to + Duration::days(1))?;
+
+    let mut cell = None;
+    let mut windows = Vec::new();
+    for start in starts {
+        let mut days = Vec::new();
+        for date in config.window.window_days(&start)? {
+            if date < first || date > last {
+                continue;
+            }
+            let file = inputs::choosefile(&date, &config.input);
+            let steps = upstream_steps(&file, args, &config, &mut cell)?;
+            days.push(Day { date, file, steps });
+        }
+        let reduced = reduced_means(&start, args, &config, &mut cell)?;
+        windows.push(Window { start, days, reduced });
+    }
+    let dump = Dump { latitude: cell.map(|c| c.0), longitude: cell.map(|c| c.1), windows };
+
+    match args.format {
+        DumpFormat::Json => println!("{}", serde_json::to_string_pretty(&dump).map_err(|e| CcmpError::Config(e.to_string()))?),
+        DumpFormat::Table => print_table(&dump),
+    }
+
+    Ok(())
+}
+
+fn print_table(dump: &Dump) {
+    // one block per window: a row per upstream step, then the reduced means and counts
+
+    let show = |x: Option<f64>| x.map(|v| format!("{:>10.4}", v)).unwrap_or_else(|| format!("{:>10}", "-"));
+    let count = |x: Option<f64>| x.map(|v| format!("{:>10}", v)).unwrap_or_else(|| format!("{:>10}", "-"));
+    match (dump.latitude, dump.longitude) {
+        (Some(lat), Some(lon)) => println!("cell latitude {} longitude {}", lat, lon),
+        _ => println!("cell not found: no upstream or reduced file in range"),
+    }
+    for window in dump.windows.iter() {
+        println!();
+        println!("window {}", window.start);
+        println!("{:<10} {:>5} {:>10} {:>10} {:>10} {:>10}", "date", "hour", "uwnd", "vwnd", "ws", "nobs");
+        for day in window.days.iter() {
+            match &day.steps {
+                Some(steps) => {
+                    for s in steps.iter() {
+                        println!("{:<10} {:>5} {} {} {} {}", day.date, s.hour, show(s.uwnd), show(s.vwnd), show(s.ws), show(s.nobs));
+                    }
+                }
+                None => println!("{:<10} missing {}", day.date, day.file),
+            }
+        }
+        match &window.reduced {
+            Some(r) => {
+                println!("{:<16} {} {} {} {}", "window mean", show(r.uwnd), show(r.vwnd), show(r.ws), show(r.nobs));
+                println!("{:<16} {} {} {} {:>10}", "window count", count(r.uwnd_nobs), count(r.vwnd_nobs), count(r.ws_nobs), "");
+                println!("{:<16} {}", "from", r.file);
+            }
+            None => println!("{:<16} not reduced", "window mean"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! The crate's error type: one variant per kind of failure, each with its own exit code,
+//! so job orchestration can tell a run worth retrying from one that needs its config or data fixed.
+
+use std::fmt;
+
+/// Everything that can stop a run, grouped by what has to change before rerunning it.
+#[derive(Debug)]
+pub enum CcmpError {
+    /// The run definition or command line asks for something impossible.
+    Config(String),
+    /// A date could not be parsed, or falls outside what the calendar can represent.
+    Date(String),
+    /// A file to be read is absent or unreadable.
+    Input { path: String, message: String },
+    /// A file was read, but lacks the variables, shape or contents expected of it.
+    Schema { path: String, message: String },
+    /// An output, checkpoint or report file could not be written.
+    Output { path: String, message: String },
+    /// The database could not be reached, or refused a write.
+    Database(String),
+}
+
+// exit codes, one per variant; 1 is left to unclassified failures and 101 to panics
+
+/// Fix the config; the same as clap's exit code for a bad command line.
+pub const EXIT_CONFIG: i32 = 2;
+/// Fix the dates given.
+pub const EXIT_DATE: i32 = 3;
+/// Fix or supply the input data.
+pub const EXIT_INPUT: i32 = 4;
+/// Fix the input data, or the config describing it.
+pub const EXIT_SCHEMA: i32 = 5;
+/// Retry once storage is available.
+pub const EXIT_OUTPUT: i32 = 6;
+/// Retry once the database is available.
+pub const EXIT_DATABASE: i32 = 7;
+
+impl CcmpError {
+    /// A file to be read could not be opened or read.
+    pub fn input(path: &str, e: impl fmt::Display) -> CcmpError {
+        CcmpError::Input { path: String::from(path), message: e.to_string() }
+    }
+
+    /// A file did not hold what was expected of it.
+    pub fn schema(path: &str, e: impl fmt::Display) -> CcmpError {
+        CcmpError::Schema { path: String::from(path), message: e.to_string() }
+    }
+
+    /// A file could not be written.
+    pub fn output(path: &str, e: impl fmt::Display) -> CcmpError {
+        CcmpError::Output { path: String::from(path), message: e.to_string() }
+    }
+
+    /// Process exit code for this kind of failure.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CcmpError::Config(_) => EXIT_CONFIG,
+            CcmpError::Date(_) => EXIT_DATE,
+            CcmpError::Input { .. } => EXIT_INPUT,
+            CcmpError::Schema { .. } => EXIT_SCHEMA,
+            CcmpError::Output { .. } => EXIT_OUTPUT,
+            CcmpError::Database(_) => EXIT_DATABASE,
+        }
+    }
+
+    /// Whether rerunning unchanged may succeed: writes and database calls can fail on
+    /// full volumes and lost connections, while the other kinds fail the same way every time.
+    pub fn retryable(&self) -> bool {
+        matches!(self, CcmpError::Output { .. } | CcmpError::Database(_))
+    }
+
+    /// What to do about it, for the operator reading the log.
+    pub fn hint(&self) -> &'static str {
+        match self {
+            CcmpError::Config(_) => "fix the config file or command line; --print-config shows the definition in effect",
+            CcmpError::Date(_) => "dates are RFC 3339, like 1993-01-03T00:00:00.000Z, and years must be representable",
+            CcmpError::Input { .. } => "check the file exists and is readable; upstream files may need their data root mounted or downloading, see inventory",
+            CcmpError::Schema { .. } => "the file is not what this run is configured for; check the grid and variables config, or replace the file",
+            CcmpError::Output { .. } => "check the output volume is mounted, writable and has space, then rerun to resume from the checkpoint",
+            CcmpError::Database(_) => "check MONGODB_URI and that the database is reachable, then rerun",
+        }
+    }
+}
+
+impl fmt::Display for CcmpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CcmpError::Config(m) => write!(f, "configuration: {}", m),
+            CcmpError::Date(m) => write!(f, "date: {}", m),
+            CcmpError::Input { path, message } => write!(f, "reading {}: {}", path, message),
+            CcmpError::Schema { path, message } => write!(f, "unexpected contents in {}: {}", path, message),
+            CcmpError::Output { path, message } => write!(f, "writing {}: {}", path, message),
+            CcmpError::Database(m) => write!(f, "database: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for CcmpError {}
@@ -0,0 +1,39 @@
+//! Locating upstream CCMP daily files.
+
+use std::path::Path;
+
+use crate::config::InputConfig;
+use crate::config::WindowConfig;
+use crate::error::CcmpError;
+
+/// Given a yyyymmdd string, return the full path and filename to the corresponding file
+/// under the first data root that has it. Roots are searched in order, so a week running into
+/// January can find the next year's files on a different mount. If no root has the file,
+/// the path under the first root is returned, and opening it will report it missing.
+pub fn choosefile(date: &str, input: &InputConfig) -> String {
+    let relative = input.filename.replace("{year}", &date[0..4]).replace("{month}", &date[4..6]).replace("{day}", &date[6..8]).replace("{date}", date);
+    let candidates: Vec<String> = input.roots.iter().map(|r| format!("{}/{}", r.trim_end_matches('/'), relative)).collect();
+
+    match candidates.iter().find(|c| Path::new(c).exists()) {
+        Some(c) => c.clone(),
+        None => candidates[0].clone(),
+    }
+}
+
+/// The daily files averaged into the window beginning at the given lattice date.
+pub fn window_files(start: &str, window: &WindowConfig, input: &InputConfig) -> Result<Vec<String>, CcmpError> {
+    Ok(window.window_days(start)?.iter().map(|d| choosefile(d, input)).collect())
+}
+
+/// Which of a week's daily files went into its means, and which could not be read.
+pub struct WeekInputs {
+    pub used: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+impl WeekInputs {
+    /// Number of daily files read.
+    pub fn ndays(&self) -> usize {
+        self.used.len()
+    }
+}
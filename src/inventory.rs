@@ -0,0 +1,36 @@
+// taking stock of which upstream files a run would need, before running it
+
+use std::path::Path;
+
+use ccmp_parse::error::CcmpError;
+use ccmp_parse::inputs;
+
+use crate::cli::InventoryArgs;
+
+pub fn run(args: &InventoryArgs) -> Result<(), CcmpError> {
+    // list each window's day count and missing files, then a total per year
+
+    let (years, config) = args.resolve()?;
+    for year in years.years() {
+        let timelattice = config.window.lattice_in_year(year)?;
+        let mut short = 0;
+        let mut absent = 0;
+        for date in timelattice.iter() {
+            let files = inputs::window_files(date, &config.window, &config.input)?;
+            let missing: Vec<&String> = files.iter().filter(|f| !Path::new(f).exists()).collect();
+            if !missing.is_empty() {
+                short += 1;
+                absent += missing.len();
+            }
+            if args.all || !missing.is_empty() {
+                println!("{} {}/{} days", date, files.len() - missing.len(), files.len());
+                for m in missing.iter() {
+                    println!("    missing {}", m);
+                }
+            }
+        }
+        println!("{}: {} windows, {} incomplete, {} upstream files missing", year, timelattice.len(), short, absent);
+    }
+
+    Ok(())
+}
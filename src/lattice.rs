@@ -0,0 +1,276 @@
+//! Generation of the time lattice that reduced products are aligned to.
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+
+use crate::error::CcmpError;
+
+/// First week start of the NOAA OISST v2 weekly means, https://psl.noaa.gov/data/gridded/data.noaa.oisst.v2.html.
+pub const OISST_ANCHOR: &str = "1993-01-03T00:00:00.000Z";
+pub const OISST_PERIOD: i64 = 7;
+
+/// Reference date for time coordinates in reduced files.
+pub const EPOCH: &str = "1993-01-01T00:00:00Z";
+pub const TIME_UNITS: &str = "days since 1993-01-01 00:00:00";
+
+/// Parse an RFC 3339 date, like "1993-01-03T00:00:00.000Z".
+pub fn parse_date(date: &str) -> Result<DateTime<FixedOffset>, CcmpError> {
+    DateTime::parse_from_rfc3339(date).map_err(|e| CcmpError::Date(format!("{} is not an RFC 3339 date like {}: {}", date, OISST_ANCHOR, e)))
+}
+
+/// Given an anchor date in the format "1993-01-03T00:00:00.000Z" and a period in days,
+/// produce every anchor + k*period falling in [start, end), in the same format.
+/// k may be negative, so the lattice extends to dates before the anchor.
+pub fn lattice(anchor: &str, period: i64, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>, CcmpError> {
+    let anchor = parse_date(anchor)?;
+    let anchordate = anchor.date_naive();
+
+    // first lattice point on or after start
+    let offset = start.signed_duration_since(anchordate).num_days();
+    let mut k = offset.div_euclid(period);
+    if offset.rem_euclid(period) != 0 {
+        k += 1;
+    }
+
+    let mut dates = Vec::new();
+    loop {
+        let d = anchor + Duration::days(k*period);
+        if d.date_naive() >= end {
+            break;
+        }
+        dates.push(d.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string());
+        k += 1;
+    }
+
+    Ok(dates)
+}
+
+/// Whole days from EPOCH to each lattice date.
+pub fn days_since_epoch(dates: &[String]) -> Result<Vec<i64>, CcmpError> {
+    let epoch = parse_date(EPOCH)?;
+    dates.iter().map(|d| Ok(parse_date(d)?.signed_duration_since(epoch).num_days())).collect()
+}
+
+/// January 1 of the year, and of the year after.
+pub fn year_bounds(year: i32) -> Result<(NaiveDate, NaiveDate), CcmpError> {
+    let start = NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| CcmpError::Date(format!("year {} is out of range", year)))?;
+    let end = NaiveDate::from_ymd_opt(year+1, 1, 1).ok_or_else(|| CcmpError::Date(format!("year {} is out of range", year+1)))?;
+
+    Ok((start, end))
+}
+
+/// Window starts anchor + k*period falling in the given calendar year.
+pub fn lattice_in_year(anchor: &str, period: i64, year: i32) -> Result<Vec<String>, CcmpError> {
+    let (start, end) = year_bounds(year)?;
+
+    lattice(anchor, period, start, end)
+}
+
+/// Given a string specifying the first date in the format "1993-02-07T00:00:00.000Z",
+/// produce a list of strings for that date and the period-1 days after it, in the format yyyymmdd.
+pub fn timewindow(start: &str, period: i64) -> Result<Vec<String>, CcmpError> {
+    let rfc3339 = parse_date(start)?;
+    let mut dates = Vec::new();
+    for i in 0..period {
+        let d = rfc3339 + Duration::days(i);
+        dates.push(format!("{}{:02}{:02}", d.year(), d.month(), d.day()));
+    }
+
+    Ok(dates)
+}
+
+/// How the calendar is divided into averaging windows.
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum WindowKind {
+    /// Windows of a fixed number of days, repeating from an anchor date; the OISST weeks by default
+    Fixed,
+    /// Calendar days, each the mean of its 6-hourly steps
+    Daily,
+    /// The 73 pentads of a year from January 1; in leap years the pentad holding February 29 has six days
+    Pentad,
+    /// Calendar months
+    Monthly,
+    /// DJF, MAM, JJA and SON; the DJF starting in a year's December runs into the next year
+    Seasonal,
+}
+
+impl WindowKind {
+    /// The longest window of the kind, in days, given the length of fixed windows.
+    pub fn max_days(&self, fixed: i64) -> i64 {
+        match self {
+            WindowKind::Fixed => fixed,
+            WindowKind::Daily => 1,
+            WindowKind::Pentad => 6,
+            WindowKind::Monthly => 31,
+            WindowKind::Seasonal => 92,
+        }
+    }
+}
+
+impl std::str::FromStr for WindowKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<WindowKind, String> {
+        match s {
+            "fixed" => Ok(WindowKind::Fixed),
+            "daily" => Ok(WindowKind::Daily),
+            "pentad" => Ok(WindowKind::Pentad),
+            "monthly" => Ok(WindowKind::Monthly),
+            "seasonal" => Ok(WindowKind::Seasonal),
+            _ => Err(format!("window kind {} should be one of fixed, daily, pentad, monthly, seasonal", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for WindowKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WindowKind::Fixed => write!(f, "fixed"),
+            WindowKind::Daily => write!(f, "daily"),
+            WindowKind::Pentad => write!(f, "pentad"),
+            WindowKind::Monthly => write!(f, "monthly"),
+            WindowKind::Seasonal => write!(f, "seasonal"),
+        }
+    }
+}
+
+// config files spell window kinds the same way as the command line
+impl TryFrom<String> for WindowKind {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<WindowKind, String> {
+        s.parse()
+    }
+}
+
+impl From<WindowKind> for String {
+    fn from(k: WindowKind) -> String {
+        k.to_string()
+    }
+}
+
+fn calendar_starts(kind: WindowKind, year: i32) -> Result<Vec<NaiveDate>, CcmpError> {
+    // every start of a calendar window in the year, in order
+
+    let (jan1, next) = year_bounds(year)?;
+    let leap = next.signed_duration_since(jan1).num_days() == 366;
+    let months = |m: &[u32]| m.iter().map(|&m| NaiveDate::from_ymd_opt(year, m, 1).ok_or_else(|| CcmpError::Date(format!("month {} of {} is out of range", m, year)))).collect();
+
+    match kind {
+        WindowKind::Fixed => Err(CcmpError::Config(String::from("fixed windows are not calendar windows"))),
+        WindowKind::Daily => Ok(jan1.iter_days().take_while(|d| *d < next).collect()),
+        // pentads after the one holding February 29, the twelfth, start a day later
+        WindowKind::Pentad => Ok((0..73).map(|k| jan1 + Duration::days(5*k + if leap && k >= 12 { 1 } else { 0 })).collect()),
+        WindowKind::Monthly => months(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]),
+        WindowKind::Seasonal => months(&[3, 6, 9, 12]),
+    }
+}
+
+/// Starts of the calendar windows of the given kind falling in [start, end), in the same format as `lattice`.
+pub fn calendar_lattice(kind: WindowKind, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>, CcmpError> {
+    let mut dates = Vec::new();
+    for year in start.year()..=end.year() {
+        for d in calendar_starts(kind, year)? {
+            if d >= start && d < end {
+                dates.push(format!("{}T00:00:00.000Z", d.format("%Y-%m-%d")));
+            }
+        }
+    }
+
+    Ok(dates)
+}
+
+/// Given the start of a calendar window of the given kind, in the format "1993-02-01T00:00:00.000Z",
+/// produce a list of strings for every day of the window, in the format yyyymmdd.
+pub fn calendar_window(kind: WindowKind, start: &str) -> Result<Vec<String>, CcmpError> {
+    let first = parse_date(start)?.date_naive();
+    // the window runs to the next start, which is at most a year on
+    let next = calendar_lattice(kind, first + Duration::days(1), first + Duration::days(367))?;
+    let end = parse_date(next.first().ok_or_else(|| CcmpError::Date(format!("no {} window follows {}", kind, start)))?)?.date_naive();
+
+    timewindow(start, end.signed_duration_since(first).num_days())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // first entry and length of each year of the lattice originally hardcoded in main;
+    // 2022 was only carried through 2022-07-31.
+    const LEGACY: [(i32, &str, usize); 30] = [
+        (1993, "1993-01-03T00:00:00.000Z", 52), (1994, "1994-01-02T00:00:00.000Z", 52),
+        (1995, "1995-01-01T00:00:00.000Z", 53), (1996, "1996-01-07T00:00:00.000Z", 52),
+        (1997, "1997-01-05T00:00:00.000Z", 52), (1998, "1998-01-04T00:00:00.000Z", 52),
+        (1999, "1999-01-03T00:00:00.000Z", 52), (2000, "2000-01-02T00:00:00.000Z", 53),
+        (2001, "2001-01-07T00:00:00.000Z", 52), (2002, "2002-01-06T00:00:00.000Z", 52),
+        (2003, "2003-01-05T00:00:00.000Z", 52), (2004, "2004-01-04T00:00:00.000Z", 52),
+        (2005, "2005-01-02T00:00:00.000Z", 52), (2006, "2006-01-01T00:00:00.000Z", 53),
+        (2007, "2007-01-07T00:00:00.000Z", 52), (2008, "2008-01-06T00:00:00.000Z", 52),
+        (2009, "2009-01-04T00:00:00.000Z", 52), (2010, "2010-01-03T00:00:00.000Z", 52),
+        (2011, "2011-01-02T00:00:00.000Z", 52), (2012, "2012-01-01T00:00:00.000Z", 53),
+        (2013, "2013-01-06T00:00:00.000Z", 52), (2014, "2014-01-05T00:00:00.000Z", 52),
+        (2015, "2015-01-04T00:00:00.000Z", 52), (2016, "2016-01-03T00:00:00.000Z", 52),
+        (2017, "2017-01-01T00:00:00.000Z", 53), (2018, "2018-01-07T00:00:00.000Z", 52),
+        (2019, "2019-01-06T00:00:00.000Z", 52), (2020, "2020-01-05T00:00:00.000Z", 52),
+        (2021, "2021-01-03T00:00:00.000Z", 52), (2022, "2022-01-02T00:00:00.000Z", 31),
+    ];
+
+    #[test]
+    fn reproduces_legacy_lattice() {
+        let mut previous: Option<DateTime<chrono::FixedOffset>> = None;
+        for (year, first, n) in LEGACY {
+            let dates = if year == 2022 {
+                lattice(OISST_ANCHOR, OISST_PERIOD, NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(), NaiveDate::from_ymd_opt(2022, 8, 1).unwrap()).unwrap()
+            } else {
+                lattice_in_year(OISST_ANCHOR, OISST_PERIOD, year).unwrap()
+            };
+            assert_eq!(dates.len(), n, "{}", year);
+            assert_eq!(dates[0], first);
+            for d in dates.iter() {
+                // legacy lattice was strictly weekly across year boundaries
+                let t = DateTime::parse_from_rfc3339(d).unwrap();
+                if let Some(p) = previous {
+                    assert_eq!(t.signed_duration_since(p).num_days(), 7, "{}", d);
+                }
+                previous = Some(t);
+            }
+        }
+    }
+
+    #[test]
+    fn extends_before_anchor() {
+        let dates = lattice_in_year(OISST_ANCHOR, OISST_PERIOD, 1992).unwrap();
+        assert_eq!(dates.first().unwrap(), "1992-01-05T00:00:00.000Z");
+        assert_eq!(dates.last().unwrap(), "1992-12-27T00:00:00.000Z");
+    }
+
+    #[test]
+    fn calendar_windows_tile_each_year() {
+        for (kind, n) in [(WindowKind::Daily, 366), (WindowKind::Pentad, 73), (WindowKind::Monthly, 12), (WindowKind::Seasonal, 4)] {
+            let (start, end) = year_bounds(2020).unwrap();
+            let starts = calendar_lattice(kind, start, end).unwrap();
+            assert_eq!(starts.len(), n, "{}", kind);
+            // every window ends the day before the next begins
+            for pair in starts.windows(2) {
+                let days = calendar_window(kind, &pair[0]).unwrap();
+                let next = parse_date(&pair[1]).unwrap();
+                assert_eq!(days.last().unwrap(), &(next - Duration::days(1)).format("%Y%m%d").to_string(), "{} {}", kind, pair[0]);
+            }
+        }
+        let pentads = calendar_lattice(WindowKind::Pentad, NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()).unwrap();
+        assert_eq!(calendar_window(WindowKind::Pentad, &pentads[11]).unwrap(), ["20200225", "20200226", "20200227", "20200228", "20200229", "20200301"]);
+        assert_eq!(calendar_window(WindowKind::Pentad, &pentads[72]).unwrap().last().unwrap(), "20201231");
+        let djf = calendar_window(WindowKind::Seasonal, "1993-12-01T00:00:00.000Z").unwrap();
+        assert_eq!((djf.len(), djf.last().unwrap().as_str()), (90, "19940228"));
+    }
+
+    #[test]
+    fn bad_anchor_is_a_date_error() {
+        assert!(matches!(lattice_in_year("1993-01-03", OISST_PERIOD, 1993), Err(CcmpError::Date(_))));
+        assert!(matches!(timewindow("last tuesday", OISST_PERIOD), Err(CcmpError::Date(_))));
+    }
+}
@@ -0,0 +1,43 @@
+//! Reduction of REMSS CCMP 6-hourly 10 m winds to OISST-aligned weekly means, as used by Argovis,
+//! or to daily, pentad, monthly or seasonal means.
+//!
+//! The pieces are usable on their own:
+//! - [`lattice`] generates the window start dates reduced products are aligned to, fixed or calendar,
+//! - [`inputs`] resolves the upstream daily files each window is averaged from,
+//! - [`reduce`] reads those files into an [`accumulator::Accumulator`], optionally several windows at once,
+//! - [`stress`] turns each 6-hourly wind into a surface stress before it is averaged,
+//! - [`writer`] lays out and fills the CF-1.8 netcdf output,
+//! - [`basin`] tags grid cells with their Argovis ocean basin,
+//! - [`config`] holds a complete run definition, and [`run::reduce_year`] carries one out,
+//!   with [`checkpoint`] and [`report`] recording its progress and inputs,
+//! - [`climatology`] averages reduced files over baseline years, and subtracts that average to give anomalies,
+//! - [`proofread`] recomputes values of a finished file from its upstream files, picked with [`rng`],
+//! - [`error`] classifies everything that can go wrong, with an exit code for each kind.
+//!
+//! A minimal reduction of one week of CCMP files into running sums:
+//!
+//! ```no_run
+//! use ccmp_parse::{accumulator::Accumulator, config::InputConfig, config::WindowConfig, inputs, reduce, stress::Stress};
+//!
+//! let files = inputs::window_files("1993-01-03T00:00:00.000Z", &WindowConfig::default(), &InputConfig::default())?;
+//! let mut acc = Accumulator::new(&reduce::CCMP_GRID, &Stress::default());
+//! let used = reduce::reduce_week(&files, &mut acc, &reduce::CCMP_GRID, &reduce::Variables::default())?;
+//! println!("{} of {} days read", used.ndays(), files.len());
+//! # Ok::<(), ccmp_parse::error::CcmpError>(())
+//! ```
+
+pub mod accumulator;
+pub mod basin;
+pub mod checkpoint;
+pub mod climatology;
+pub mod config;
+pub mod error;
+pub mod inputs;
+pub mod lattice;
+pub mod proofread;
+pub mod reduce;
+pub mod report;
+pub mod rng;
+pub mod run;
+pub mod stress;
+pub mod writer;
@@ -0,0 +1,117 @@
+// publishing reduced files to mongodb, one time series document per grid cell
+
+use bson::doc;
+use bson::Bson;
+use bson::Document;
+use chrono::Duration;
+
+use ccmp_parse::basin::find_basin;
+use ccmp_parse::basin::tidylon;
+use ccmp_parse::config;
+use ccmp_parse::error::CcmpError;
+use ccmp_parse::lattice;
+use ccmp_parse::writer::Unpacking;
+use ccmp_parse::writer::QUANTITIES;
+
+use crate::cli::LoadDbArgs;
+
+fn read_row(path: &str, outfile: &netcdf::File, var: &str, lat: usize) -> Result<Vec<Option<f64>>, CcmpError> {
+    // every time step of one latitude row of a reduced variable, in [time][longitude] order,
+    // unpacked to physical units and with fill values as None
+
+    let v = outfile.variable(var).ok_or_else(|| CcmpError::schema(path, format!("no {} variable", var)))?;
+    let unpacking = Unpacking::of(&v);
+
+    Ok(v.values::<f64, _>((.., lat, ..)).map_err(|e| CcmpError::input(path, e))?.iter().map(|&x| unpacking.unpack(x)).collect())
+}
+
+fn string_attribute(var: &netcdf::Variable, name: &str) -> String {
+    match var.attribute(name).and_then(|a| a.value().ok()) {
+        Some(netcdf::attribute::AttrValue::Str(s)) => s,
+        _ => String::new(),
+    }
+}
+
+fn database(e: mongodb::error::Error) -> CcmpError {
+    CcmpError::Database(e.to_string())
+}
+
+pub fn run(args: &LoadDbArgs) -> Result<(), CcmpError> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| CcmpError::Database(format!("starting the async runtime: {}", e)))?;
+    runtime.block_on(load(args))
+}
+
+async fn load(args: &LoadDbArgs) -> Result<(), CcmpError> {
+    // stitch every year's weeks into one time series per cell, and replace those cells' documents.
+    // cells with no data in any week, i.e. land and sea ice, get no document.
+
+    let outpaths: Vec<String> = args.years.years().map(|y| config::outfile_path(&args.out, y)).collect();
+    let outfiles = outpaths.iter().map(|p| netcdf::open(p).map_err(|e| CcmpError::input(p, e))).collect::<Result<Vec<_>, _>>()?;
+    let basinfile = netcdf::open(&args.basins).map_err(|e| CcmpError::input(&args.basins, e))?;
+    let basins = basinfile.variable(&args.basin_var).ok_or_else(|| CcmpError::schema(&args.basins, format!("no {} variable", args.basin_var)))?;
+
+    let client = mongodb::Client::with_uri_str(&args.mongodb_uri).await.map_err(database)?;
+    let db = client.database(&args.database);
+    let cells = db.collection::<Document>(&args.collection);
+    let meta = db.collection::<Document>(&format!("{}Meta", args.collection));
+
+    // time series dates, and units and long names, for the metadata document
+    let epoch = lattice::parse_date(lattice::EPOCH)?;
+    let mut timeseries = Vec::new();
+    for (p, f) in outpaths.iter().zip(outfiles.iter()) {
+        let time = f.variable("time").ok_or_else(|| CcmpError::schema(p, "no time variable"))?.values::<i64, _>(..).map_err(|e| CcmpError::input(p, e))?;
+        timeseries.extend(time.iter().map(|&t| (epoch + Duration::days(t)).format("%Y-%m-%dT%H:%M:%SZ").to_string()));
+    }
+    let first = &outfiles[0];
+    let info: Vec<Bson> = QUANTITIES.iter().map(|var| {
+        let v = first.variable(var);
+        let units = v.as_ref().map(|v| string_attribute(v, "units")).unwrap_or_default();
+        let long_name = v.as_ref().map(|v| string_attribute(v, "long_name")).unwrap_or_default();
+        Bson::from(vec![units, long_name])
+    }).collect();
+    let metadoc = doc! {
+        "_id": &args.collection,
+        "data_type": "wind",
+        "date_updated_argovis": bson::DateTime::now(),
+        "timeseries": timeseries.clone(),
+        "data_info": [QUANTITIES.to_vec(), ["units", "long_name"], info],
+    };
+    meta.replace_one(doc! {"_id": &args.collection}, metadoc, mongodb::options::ReplaceOptions::builder().upsert(true).build()).await.map_err(database)?;
+
+    let firstpath = &outpaths[0];
+    let latitude = first.variable("latitude").ok_or_else(|| CcmpError::schema(firstpath, "no latitude variable"))?.values::<f64, _>(..).map_err(|e| CcmpError::input(firstpath, e))?;
+    let longitude = first.variable("longitude").ok_or_else(|| CcmpError::schema(firstpath, "no longitude variable"))?.values::<f64, _>(..).map_err(|e| CcmpError::input(firstpath, e))?;
+    let mut loaded = 0;
+    for (latidx, &lat) in latitude.iter().enumerate() {
+        // rows[year][variable] holds [time][longitude] values for this latitude
+        let rows = outpaths.iter().zip(outfiles.iter()).map(|(p, f)| QUANTITIES.iter().map(|var| read_row(p, f, var, latidx)).collect::<Result<Vec<_>, _>>()).collect::<Result<Vec<_>, _>>()?;
+
+        let mut docs = Vec::new();
+        for (lonidx, &lon) in longitude.iter().enumerate() {
+            let data: Vec<Vec<Option<f64>>> = (0..QUANTITIES.len()).map(|v| {
+                rows.iter().flat_map(|year| year[v].iter().skip(lonidx).step_by(longitude.len()).copied()).collect()
+            }).collect();
+            if data[0].iter().all(|x| x.is_none()) {
+                continue;
+            }
+            let lon = tidylon(lon);
+            docs.push(doc! {
+                "_id": format!("{}_{}", lon, lat),
+                "metadata": [&args.collection],
+                "basin": find_basin(&basins, lon, lat).map_err(|e| CcmpError::schema(&args.basins, e))?,
+                "geolocation": {"type": "Point", "coordinates": [lon, lat]},
+                "data": data,
+            });
+        }
+        if docs.is_empty() {
+            continue;
+        }
+        let ids: Vec<Bson> = docs.iter().filter_map(|d| d.get("_id").cloned()).collect();
+        cells.delete_many(doc! {"_id": {"$in": ids}}, None).await.map_err(database)?;
+        loaded += docs.len();
+        cells.insert_many(docs, None).await.map_err(database)?;
+    }
+    println!("loaded {} cells of {} weeks into {}.{}", loaded, timeseries.len(), args.database, args.collection);
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+//! Checks of reduced files against their upstream files, recomputing each checked value
+//! straight from the upstream values rather than through the accumulator: sampled or
+//! exhaustive pass/fail checks, and per-week error statistics against a reference reduction.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::config::RunConfig;
+use crate::error::CcmpError;
+use crate::inputs;
+use crate::reduce::Grid;
+use crate::rng::SplitMix64;
+use crate::writer::Unpacking;
+use crate::writer::QUANTITIES;
+
+// past this many cells of one week and quantity, one whole-field read is cheaper than a library call per cell
+const WHOLE_FIELD_CELLS: usize = 4096;
+
+// agreement allowed on top of any packing resolution: the absolute tolerance of the old proofread.py,
+// plus a relative term for nobs totals large enough to lose digits as f32
+const ABS_TOL: f64 = 1e-5;
+const REL_TOL: f64 = 1e-6;
+
+/// Which values of a reduced file to check.
+pub enum Coverage {
+    /// `count` random (week, cell, quantity) picks, from a generator seeded with `seed`.
+    Samples { count: usize, seed: u64 },
+    /// Every quantity of every cell of every week.
+    Full,
+}
+
+/// A stored value alongside its recomputation from the upstream files.
+pub struct Difference {
+    pub date: String,
+    pub quantity: &'static str,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Mean, or total for nobs, of the valid upstream values; None if there were none.
+    pub expected: Option<f64>,
+    pub stored: Option<f64>,
+    /// Number of valid upstream values, and the stored `*_nobs` count; None for nobs, which has no count.
+    pub expected_count: Option<i32>,
+    pub stored_count: Option<i32>,
+    /// Largest difference the stored type and floating point arithmetic account for.
+    pub tolerance: f64,
+}
+
+impl Difference {
+    /// Absolute difference of the values; infinite if only one of them is fill.
+    pub fn error(&self) -> f64 {
+        match (self.expected, self.stored) {
+            (Some(e), Some(s)) => (e - s).abs(),
+            (None, None) => 0.0,
+            _ => f64::INFINITY,
+        }
+    }
+
+    /// Whether the stored value and count are what the upstream values give.
+    pub fn agrees(&self) -> bool {
+        self.error() <= self.tolerance && self.expected_count == self.stored_count
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = |v: Option<f64>| v.map(|x| x.to_string()).unwrap_or_else(|| String::from("fill"));
+        let count = |c: Option<i32>| c.map(|n| format!(" from {} values", n)).unwrap_or_default();
+        write!(f, "{} {} at latitude {} longitude {}: expected {}{}, stored {}{}",
+            self.date, self.quantity, self.latitude, self.longitude,
+            value(self.expected), count(self.expected_count), value(self.stored), count(self.stored_count))
+    }
+}
+
+/// What checking one reduced file found.
+pub struct Summary {
+    pub checked: usize,
+    pub mismatches: Vec<Difference>,
+}
+
+/// How far one quantity of one reduced week is from the reference reduction of its upstream files.
+pub struct QuantityErrors {
+    pub quantity: &'static str,
+    pub cells: usize,
+    /// Largest absolute error among cells where both sides have a value.
+    pub max_abs_error: f64,
+    /// Cells whose value or count disagrees beyond tolerance, including those where only one side is fill.
+    pub mismatches: usize,
+    /// Cells whose `*_nobs` count disagrees; always 0 for nobs.
+    pub count_mismatches: usize,
+    /// The cells furthest off, those that disagree first and then by error, leaving out exact agreement.
+    pub worst: Vec<Difference>,
+}
+
+/// Check a reduced file, whose time steps start at the dates of `timelattice`, against the
+/// upstream files `config` resolves for each window. Upstream files that cannot be opened
+/// are left out, as the reduction itself leaves them out.
+pub fn check_file(outpath: &str, timelattice: &[String], config: &RunConfig, coverage: &Coverage) -> Result<Summary, CcmpError> {
+    let reduced = Reduced::open(outpath)?;
+    let ncells = reduced.ncells();
+    let mut summary = Summary { checked: 0, mismatches: Vec::new() };
+    if timelattice.is_empty() || ncells == 0 {
+        return Ok(summary);
+    }
+
+    // cells to check for each sampled week and quantity, so each week's upstream files are opened once
+    let mut sampled: BTreeMap<usize, Vec<Vec<usize>>> = BTreeMap::new();
+    if let Coverage::Samples { count, seed } = coverage {
+        let mut rng = SplitMix64::new(*seed);
+        for _ in 0..*count {
+            let week = rng.below(timelattice.len());
+            let cell = rng.below(ncells);
+            let q = rng.below(QUANTITIES.len());
+            sampled.entry(week).or_insert_with(|| vec![Vec::new(); QUANTITIES.len()])[q].push(cell);
+        }
+    }
+    let every: Vec<usize> = match coverage {
+        Coverage::Full => (0..ncells).collect(),
+        Coverage::Samples { .. } => Vec::new(),
+    };
+
+    for (week, date) in timelattice.iter().enumerate() {
+        let cells: Vec<&[usize]> = match coverage {
+            Coverage::Full => vec![&every; QUANTITIES.len()],
+            Coverage::Samples { .. } => match sampled.get(&week) {
+                Some(c) => c.iter().map(|v| v.as_slice()).collect(),
+                None => continue,
+            },
+        };
+        let upstream = open_upstream(date, config)?;
+        for (q, &quantity) in QUANTITIES.iter().enumerate() {
+            if cells[q].is_empty() {
+                continue;
+            }
+            let compared = reduced.compare(&upstream, config, week, date, quantity, cells[q])?;
+            summary.checked += compared.len();
+            summary.mismatches.extend(compared.into_iter().filter(|d| !d.agrees()));
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Recompute every cell of the week starting at `timelattice[week]` with the reference reduction,
+/// and measure each quantity's errors against the reduced file, keeping the `worst` cells of each.
+pub fn reference_week(outpath: &str, timelattice: &[String], week: usize, config: &RunConfig, worst: usize) -> Result<Vec<QuantityErrors>, CcmpError> {
+    let reduced = Reduced::open(outpath)?;
+    let every: Vec<usize> = (0..reduced.ncells()).collect();
+    let date = &timelattice[week];
+    let upstream = open_upstream(date, config)?;
+
+    let mut errors = Vec::new();
+    for quantity in QUANTITIES {
+        let mut compared = reduced.compare(&upstream, config, week, date, quantity, &every)?;
+        let max_abs_error = compared.iter().map(|d| d.error()).filter(|e| e.is_finite()).fold(0.0, f64::max);
+        let mismatches = compared.iter().filter(|d| !d.agrees()).count();
+        let count_mismatches = compared.iter().filter(|d| d.expected_count != d.stored_count).count();
+        compared.retain(|d| d.error() > 0.0 || d.expected_count != d.stored_count);
+        compared.sort_by(|a, b| a.agrees().cmp(&b.agrees()).then(b.error().total_cmp(&a.error())));
+        compared.truncate(worst);
+        errors.push(QuantityErrors { quantity, cells: every.len(), max_abs_error, mismatches, count_mismatches, worst: compared });
+    }
+
+    Ok(errors)
+}
+
+fn open_upstream(date: &str, config: &RunConfig) -> Result<Vec<(String, netcdf::File)>, CcmpError> {
+    // the window's upstream files that open, leaving out the rest as the reduction does
+
+    let files = inputs::window_files(date, &config.window, &config.input)?;
+    Ok(files.into_iter().filter_map(|f| {
+        let opened = netcdf::open(&f).ok()?;
+        Some((f, opened))
+    }).collect())
+}
+
+// a reduced file open for comparison, with its grid
+struct Reduced {
+    path: String,
+    file: netcdf::File,
+    latitude: Vec<f64>,
+    longitude: Vec<f64>,
+}
+
+impl Reduced {
+    fn open(path: &str) -> Result<Reduced, CcmpError> {
+        let file = netcdf::open(path).map_err(|e| CcmpError::input(path, e))?;
+        let latitude = coordinate(path, &file, "latitude")?;
+        let longitude = coordinate(path, &file, "longitude")?;
+        Ok(Reduced { path: String::from(path), file, latitude, longitude })
+    }
+
+    fn ncells(&self) -> usize {
+        self.latitude.len()*self.longitude.len()
+    }
+
+    fn compare(&self, upstream: &[(String, netcdf::File)], config: &RunConfig, week: usize, date: &str, quantity: &'static str, cells: &[usize]) -> Result<Vec<Difference>, CcmpError> {
+        // one quantity of one week at the given cells, as the reference reduction gives it and as stored
+
+        let nlon = self.longitude.len();
+        let q = QUANTITIES.iter().position(|&n| n == quantity).unwrap_or(0);
+        let sums = reference_sums(upstream, config.variables.names()[q], cells, &config.grid)?;
+        let (stored, rounding) = stored_values(&self.path, &self.file, quantity, week, cells, nlon)?;
+        let counts = if quantity == "nobs" {
+            None
+        } else {
+            Some(stored_values(&self.path, &self.file, &format!("{}_nobs", quantity), week, cells, nlon)?.0)
+        };
+
+        Ok(cells.iter().enumerate().map(|(i, &cell)| {
+            let (sum, n) = sums[i];
+            let expected = match (n, quantity) {
+                (0, _) => None,
+                (_, "nobs") => Some(sum),
+                _ => Some(sum / n as f64),
+            };
+            Difference {
+                date: String::from(date),
+                quantity,
+                latitude: self.latitude[cell / nlon],
+                longitude: self.longitude[cell % nlon],
+                expected,
+                stored: stored[i],
+                expected_count: counts.as_ref().map(|_| n),
+                stored_count: counts.as_ref().map(|c| c[i].unwrap_or(0.0) as i32),
+                tolerance: rounding + ABS_TOL + REL_TOL*expected.unwrap_or(0.0).abs(),
+            }
+        }).collect())
+    }
+}
+
+fn coordinate(path: &str, f: &netcdf::File, name: &str) -> Result<Vec<f64>, CcmpError> {
+    f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("no {} variable", name)))?
+        .values::<f64, _>(..).map_err(|e| CcmpError::input(path, e))
+}
+
+fn reference_sums(upstream: &[(String, netcdf::File)], name: &str, cells: &[usize], grid: &Grid) -> Result<Vec<(f64, i32)>, CcmpError> {
+    // the reference reduction, written as directly as the definition: sum and number of the non-NaN
+    // upstream values at each cell, over every step of every file, in file order then step order
+
+    let mut sums = vec![(0.0, 0); cells.len()];
+    for (path, f) in upstream {
+        let v = f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("variable {} not found", name)))?;
+        let mut add = |i: usize, steps: &[f64]| {
+            for &x in steps.iter().filter(|x| !x.is_nan()) {
+                sums[i].0 += x;
+                sums[i].1 += 1;
+            }
+        };
+        if cells.len() > WHOLE_FIELD_CELLS {
+            let values = v.values::<f64, _>(..).map_err(|e| CcmpError::input(path, e))?;
+            let nsteps = grid.nsteps;
+            if values.len() != grid.ncells()*nsteps {
+                return Err(CcmpError::schema(path, format!("variable {} has {} values, but the grid is configured with {} (nlat*nlon*nsteps)", name, values.len(), grid.ncells()*nsteps)));
+            }
+            for (i, &cell) in cells.iter().enumerate() {
+                add(i, &values[cell*nsteps..(cell+1)*nsteps]);
+            }
+        } else {
+            for (i, &cell) in cells.iter().enumerate() {
+                let steps = v.values::<f64, _>((cell / grid.nlon, cell % grid.nlon, ..)).map_err(|e| CcmpError::input(path, e))?;
+                add(i, &steps);
+            }
+        }
+    }
+
+    Ok(sums)
+}
+
+fn stored_values(path: &str, outfile: &netcdf::File, name: &str, week: usize, cells: &[usize], nlon: usize) -> Result<(Vec<Option<f64>>, f64), CcmpError> {
+    // unpacked values of a reduced variable at the given cells of one week, with the rounding its storage allows
+
+    let v = outfile.variable(name).ok_or_else(|| CcmpError::schema(path, format!("no {} variable", name)))?;
+    let unpacking = Unpacking::of(&v);
+    let raw = if cells.len() > WHOLE_FIELD_CELLS {
+        let field = v.values::<f64, _>((week, .., ..)).map_err(|e| CcmpError::input(path, e))?;
+        cells.iter().map(|&cell| field[cell]).collect()
+    } else {
+        cells.iter().map(|&cell| v.value::<f64, _>([week, cell / nlon, cell % nlon])).collect::<Result<Vec<f64>, _>>().map_err(|e| CcmpError::input(path, e))?
+    };
+
+    Ok((raw.iter().map(|&x| unpacking.unpack(x)).collect(), unpacking.rounding()))
+}
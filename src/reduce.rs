@@ -0,0 +1,219 @@
+//! Reading upstream CCMP daily files and folding them into running sums.
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::accumulator::Accumulator;
+use crate::error::CcmpError;
+use crate::inputs::WeekInputs;
+use crate::stress::Stress;
+
+/// Shape of every upstream daily variable, [latitude][longitude][time].
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Grid {
+    pub nlat: usize,
+    pub nlon: usize,
+    pub nsteps: usize,
+}
+
+/// The quarter degree, 6-hourly CCMP V3.0 grid.
+pub const CCMP_GRID: Grid = Grid { nlat: 720, nlon: 1440, nsteps: 4 };
+
+impl Grid {
+    /// Number of latitude, longitude cells.
+    pub fn ncells(&self) -> usize {
+        self.nlat*self.nlon
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Grid {
+        CCMP_GRID
+    }
+}
+
+/// Names of the upstream variables read into each accumulated quantity.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Variables {
+    pub uwnd: String,
+    pub vwnd: String,
+    pub ws: String,
+    pub nobs: String,
+}
+
+impl Default for Variables {
+    fn default() -> Variables {
+        Variables {
+            uwnd: String::from("uwnd"),
+            vwnd: String::from("vwnd"),
+            ws: String::from("ws"),
+            nobs: String::from("nobs"),
+        }
+    }
+}
+
+impl Variables {
+    /// The upstream names, in uwnd, vwnd, ws, nobs order.
+    pub fn names(&self) -> [&str; 4] {
+        [&self.uwnd, &self.vwnd, &self.ws, &self.nobs]
+    }
+}
+
+/// Make sure an upstream file has its coordinates and every configured variable, in the configured shape,
+/// before anything is read from it on that assumption.
+pub fn check_upstream(path: &str, f: &netcdf::File, grid: &Grid, variables: &Variables) -> Result<(), CcmpError> {
+    for (name, len) in [("latitude", grid.nlat), ("longitude", grid.nlon)] {
+        let v = f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("coordinate variable {} not found", name)))?;
+        if v.len() != len {
+            return Err(CcmpError::schema(path, format!("{} has {} points, but the grid is configured with {}", name, v.len(), len)));
+        }
+    }
+    let expected = [grid.nlat, grid.nlon, grid.nsteps];
+    for name in variables.names() {
+        let v = f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("variable {} not found", name)))?;
+        let shape: Vec<usize> = v.dimensions().iter().map(|d| d.len()).collect();
+        if shape != expected {
+            return Err(CcmpError::schema(path, format!("variable {} has shape {:?}, but the grid is configured as {:?} (nlat, nlon, nsteps)", name, shape, expected)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whole-day hyperslabs in upstream [latitude][longitude][time] order.
+pub struct DailyFields {
+    pub uwnd: Vec<f64>,
+    pub vwnd: Vec<f64>,
+    pub ws: Vec<f64>,
+    pub nobs: Vec<f64>,
+}
+
+/// One bulk read per variable, rather than one FFI call per value.
+/// Every variable must hold exactly one value per cell and step of the grid, whatever the first file checked held.
+pub fn read_daily(path: &str, f: &netcdf::File, grid: &Grid, variables: &Variables) -> Result<DailyFields, CcmpError> {
+    let expected = grid.ncells()*grid.nsteps;
+    let read = |name: &str| {
+        let v = f.variable(name).ok_or_else(|| CcmpError::schema(path, format!("variable {} not found", name)))?;
+        let values = v.values::<f64, _>(..).map_err(|e| CcmpError::input(path, format!("variable {}: {}", name, e)))?;
+        if values.len() != expected {
+            return Err(CcmpError::schema(path, format!("variable {} has {} values, but the grid is configured with {} (nlat*nlon*nsteps)", name, values.len(), expected)));
+        }
+        Ok(values)
+    };
+
+    Ok(DailyFields {
+        uwnd: read(&variables.uwnd)?,
+        vwnd: read(&variables.vwnd)?,
+        ws: read(&variables.ws)?,
+        nobs: read(&variables.nobs)?,
+    })
+}
+
+/// Sum up every available daily file for one week into a freshly reset accumulator, in the order given.
+/// Files that cannot be opened are recorded as missing; files that open but cannot be read are errors.
+pub fn reduce_week(files: &[String], acc: &mut Accumulator, grid: &Grid, variables: &Variables) -> Result<WeekInputs, CcmpError> {
+    acc.reset();
+    let mut inputs = WeekInputs { used: Vec::new(), missing: Vec::new() };
+    for file in files.iter(){
+        let f = match netcdf::open(file) {
+            Ok(i) => i,
+            Err(_) => {
+                inputs.missing.push(file.clone());
+                continue;
+            }
+        };
+        acc.add_daily(&read_daily(file, &f, grid, variables)?);
+        inputs.used.push(file.clone());
+    }
+
+    Ok(inputs)
+}
+
+/// Reduce the weeks indexed by todo in batches of up to `threads` at once, each thread reusing its own accumulator,
+/// and hand every finished week to on_week, in the order given, along with its index and inputs.
+/// Every week is reduced by reduce_week alone, so results are identical for any thread count.
+/// netcdf-rs serializes library calls behind a global lock, so the gain is in accumulation, not I/O.
+pub fn reduce_weeks<F, E>(weeks: &[Vec<String>], todo: &[usize], threads: usize, grid: &Grid, variables: &Variables, stress: &Stress, mut on_week: F) -> Result<(), E>
+where
+    F: FnMut(usize, &Accumulator, &WeekInputs) -> Result<(), E>,
+    E: From<CcmpError>
+{
+    let mut pool: Vec<Accumulator> = (0..threads.clamp(1, todo.len().max(1))).map(|_| Accumulator::new(grid, stress)).collect();
+    for batch in todo.chunks(pool.len()) {
+        let results: Vec<Result<WeekInputs, CcmpError>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = batch.iter().zip(pool.iter_mut()).map(|(&w, acc)| {
+                scope.spawn(move || reduce_week(&weeks[w], acc, grid, variables))
+            }).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        for ((result, acc), &w) in results.into_iter().zip(pool.iter()).zip(batch) {
+            on_week(w, acc, &result?)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accumulator::accumulate;
+    use std::time::Instant;
+
+    fn time_it<T>(label: &str, f: impl FnOnce() -> T) -> T {
+        // run f, reporting its wall time
+        let start = Instant::now();
+        let result = f();
+        println!("{}: {:.3}s", label, start.elapsed().as_secs_f64());
+        result
+    }
+
+    fn legacy_accumulate(f: &netcdf::File, var: &str, sum: &mut [Vec<f64>], count: &mut [Vec<i32>]) {
+        // the per-value read loop bulk reads replaced
+        let map = f.variable(var).unwrap();
+        for lat in 0..CCMP_GRID.nlat {
+            for lon in 0..CCMP_GRID.nlon {
+                for ts in 0..CCMP_GRID.nsteps {
+                    let v = map.value::<f64, _>([lat, lon, ts]).unwrap();
+                    if !v.is_nan() {
+                        if sum[lat][lon] == -999.9 {
+                            sum[lat][lon] = 0.0;
+                        }
+                        sum[lat][lon] += v;
+                        count[lat][lon] += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_bulk_vs_per_value() {
+        // CCMP_BENCH_FILE=/tmp/y1993/m01/CCMP_Wind_Analysis_19930103_V03.0_L4.0.nc cargo test --release -- --ignored --nocapture
+        let path = std::env::var("CCMP_BENCH_FILE").expect("set CCMP_BENCH_FILE to an upstream daily file");
+        let f = netcdf::open(&path).unwrap();
+
+        let (nlat, nlon) = (CCMP_GRID.nlat, CCMP_GRID.nlon);
+        let mut legacy_sum = vec![vec![-999.9; nlon]; nlat];
+        let mut legacy_count = vec![vec![0; nlon]; nlat];
+        time_it("per-value uwnd", || legacy_accumulate(&f, "uwnd", &mut legacy_sum, &mut legacy_count));
+
+        let mut sum = vec![-999.9; nlat*nlon];
+        let mut count = vec![0; nlat*nlon];
+        time_it("bulk read of all four variables, uwnd accumulation", || {
+            let daily = read_daily(&path, &f, &CCMP_GRID, &Variables::default()).unwrap();
+            accumulate(&mut sum, Some(&mut count), &daily.uwnd, CCMP_GRID.nsteps);
+        });
+
+        for lat in 0..nlat {
+            for lon in 0..nlon {
+                assert_eq!(sum[lat*nlon + lon].to_bits(), legacy_sum[lat][lon].to_bits());
+                assert_eq!(count[lat*nlon + lon], legacy_count[lat][lon]);
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Per-week input coverage, and the JSON run report built from it.
+
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+
+use crate::config::RunConfig;
+use crate::inputs::WeekInputs;
+
+/// The upstream files one week of output was built from.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WeekRecord {
+    pub date: String,
+    pub ndays: usize,
+    pub inputs: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+impl WeekRecord {
+    /// Record the inputs of the week starting at the given lattice date.
+    pub fn new(date: &str, inputs: &WeekInputs) -> WeekRecord {
+        WeekRecord {
+            date: String::from(date),
+            ndays: inputs.ndays(),
+            inputs: inputs.used.clone(),
+            missing: inputs.missing.clone(),
+        }
+    }
+}
+
+/// Everything one year's output was built from, and how.
+#[derive(Serialize)]
+pub struct RunReport {
+    pub output: String,
+    pub year: i32,
+    pub generated: String,
+    pub config: RunConfig,
+    pub weeks: Vec<WeekRecord>,
+}
+
+/// The report lives next to the output file it describes.
+pub fn report_path(outpath: &str) -> String {
+    format!("{}.report.json", outpath)
+}
+
+/// Write a report as pretty-printed JSON.
+pub fn write_report(path: &str, report: &RunReport) -> std::io::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(report)?)
+}
@@ -0,0 +1,46 @@
+//! A small seedable pseudorandom generator, so sampled checks can be repeated exactly.
+
+/// SplitMix64, after Steele, Lea and Flood, "Fast splittable pseudorandom number generators" (2014).
+/// Not for cryptography; every seed, including 0, gives a full-period stream.
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// A generator whose stream is fixed by the seed.
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    /// The next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform index in [0, n); n must be positive.
+    pub fn below(&mut self, n: usize) -> usize {
+        // multiply-shift rather than modulo; the bias is at most n / 2^64
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_streams_repeat() {
+        let mut a = SplitMix64::new(7);
+        let mut b = SplitMix64::new(7);
+        let picks: Vec<usize> = (0..1000).map(|_| a.below(53)).collect();
+        assert_eq!(picks, (0..1000).map(|_| b.below(53)).collect::<Vec<_>>());
+        assert!(picks.iter().all(|&p| p < 53));
+        // every week of a year gets picked given enough samples
+        assert!((0..53).all(|w| picks.contains(&w)));
+        assert_ne!(SplitMix64::new(8).next_u64(), SplitMix64::new(7).next_u64());
+    }
+}
@@ -0,0 +1,128 @@
+//! Running a configured reduction, one year at a time.
+
+use chrono::Utc;
+use chrono::Datelike;
+use chrono::Timelike;
+use std::path::Path;
+
+use crate::checkpoint;
+use crate::config::outfile_path;
+use crate::config::OutputConfig;
+use crate::config::RunConfig;
+use crate::error::CcmpError;
+use crate::inputs;
+use crate::lattice;
+use crate::reduce;
+use crate::report;
+use crate::writer;
+
+/// The current UTC time as an ISO 8601 string, like 2023-06-01T12:00:00Z.
+pub fn nowstring() -> String{
+    let now = Utc::now();
+    format!("{}-{:02}-{:02}T{:02}:{:02}:{:02}Z", now.year(), now.month(), now.day(), now.hour(), now.minute(), now.second())
+}
+
+fn check_resumable(outpath: &str, config: &RunConfig) -> Result<bool, CcmpError> {
+    // an interrupted output can only be finished under the run definition it was started with;
+    // the years and thread count don't change what is written. An output left unreadable by
+    // the interruption can't be finished at all, so is started over rather than failing every retry
+
+    let recorded = match netcdf::open(outpath) {
+        Ok(outfile) => writer::read_run_config(&outfile).and_then(|s| toml::from_str::<RunConfig>(&s).ok())
+            .ok_or_else(|| String::from("no readable run_config attribute")),
+        Err(e) => Err(e.to_string()),
+    };
+    let recorded = match recorded {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("WARNING: can't resume {}, starting it over: {}", outpath, e);
+            return Ok(false);
+        }
+    };
+    let normalize = |c: &RunConfig| RunConfig { years: None, output: OutputConfig { threads: 1, ..c.output.clone() }, ..c.clone() };
+    if normalize(&recorded) != normalize(config) {
+        return Err(CcmpError::Config(format!("{} was started under a different run definition than this one, recorded in its run_config attribute; \
+            rerun with that definition to resume, or remove it and its checkpoint to start over", outpath)));
+    }
+
+    Ok(true)
+}
+
+/// Reduce one year of upstream files into the configured output, resuming from a checkpoint if one is found.
+///
+/// Writes the output named by `config.output.path`, and a run report next to it; see [`report`].
+pub fn reduce_year(year: i32, config: &RunConfig) -> Result<(), CcmpError> {
+    let roots = &config.input.roots;
+    let layout = config.output.layout();
+
+    let timelattice = config.window.lattice_in_year(year)?;
+
+    // determine which daily files to average for each week
+    let weekfiles: Vec<Vec<String>> = timelattice.iter().map(|d| inputs::window_files(d, &config.window, &config.input)).collect::<Result<_, _>>()?;
+
+    // caluclate intervals in days since 1993-01-01 for all timesteps, each ending where its days do
+    let timesteps = lattice::days_since_epoch(&timelattice)?;
+    let bounds: Vec<[i64; 2]> = timesteps.iter().zip(weekfiles.iter()).map(|(&t, files)| [t, t + files.len() as i64]).collect();
+
+    // check the upstream files match the configured grid and variables before reading any of them
+    let (upstreampath, upstream) = weekfiles.iter().flatten().find_map(|f| Some((f, netcdf::open(f).ok()?)))
+        .ok_or_else(|| CcmpError::input(&roots.join(", "), format!("none of the {} upstream files for {} found", weekfiles.iter().flatten().count(), year)))?;
+    reduce::check_upstream(upstreampath, &upstream, &config.grid, &config.variables)?;
+
+    // set up a new netcdf file to hold this period's averages, unless a checkpoint shows an earlier run to pick up from
+    let outpath = outfile_path(&config.output.path, year);
+    let checkpointpath = checkpoint::checkpoint_path(&outpath);
+    let resumable = Path::new(&checkpointpath).exists() && Path::new(&outpath).exists() && check_resumable(&outpath, config)?;
+    let mut checkpoint = if resumable {
+        let c = checkpoint::Checkpoint::load(&checkpointpath).map_err(|e| CcmpError::input(&checkpointpath, e))?;
+        println!("resuming {} with {} weeks already complete", outpath, c.completed());
+        c
+    } else {
+        // propagate dimensions from the first upstream file available
+        let mut outfile = writer::create(&outpath, &upstream, &bounds, &config.window, &config.stress, &nowstring(), &layout).map_err(|e| CcmpError::output(&outpath, e))?;
+        writer::write_run_config(&mut outfile, &config.to_toml()).map_err(|e| CcmpError::output(&outpath, e))?;
+        drop(outfile);
+        checkpoint::Checkpoint::fresh(&checkpointpath).map_err(|e| CcmpError::output(&checkpointpath, e))?
+    };
+    drop(upstream);
+    let todo: Vec<usize> = (0..timelattice.len()).filter(|&i| !checkpoint.is_done(&timelattice[i])).collect();
+
+    // weeks running into the next year need that year's files too; find gaps before spending hours on the rest
+    let require_full_weeks = config.output.require_full_weeks;
+    if require_full_weeks {
+        let absent: Vec<&String> = todo.iter().flat_map(|&i| weekfiles[i].iter()).filter(|f| !Path::new(f).exists()).collect();
+        if !absent.is_empty() {
+            return Err(CcmpError::input(absent[0], format!("not found under {:?}, one of {} missing upstream files, and --require-full-weeks is set", roots, absent.len())));
+        }
+    }
+
+    // stream each reduced week into the output
+    reduce::reduce_weeks(&weekfiles, &todo, config.output.threads, &config.grid, &config.variables, &config.stress, |time, acc, inputs| -> Result<(), CcmpError> {
+        if !inputs.missing.is_empty() {
+            if require_full_weeks {
+                return Err(CcmpError::input(&inputs.missing[0], format!("window {} has only {} of {} days of input; missing {:?}", timelattice[time], inputs.ndays(), weekfiles[time].len(), inputs.missing)));
+            }
+            eprintln!("WARNING: window {} has only {} of {} days of input; missing {:?}", timelattice[time], inputs.ndays(), weekfiles[time].len(), inputs.missing);
+        }
+        let mut outfile = netcdf::append(&outpath).map_err(|e| CcmpError::output(&outpath, e))?;
+        writer::write_week(&mut outfile, time, acc, inputs.ndays(), &layout).map_err(|e| CcmpError::output(&outpath, e))?;
+        // closing the file flushes the week to disk before it is checkpointed
+        drop(outfile);
+        checkpoint.record(report::WeekRecord::new(&timelattice[time], inputs)).map_err(|e| CcmpError::output(&checkpointpath, e))?;
+        Ok(())
+    })?;
+
+    // summarize what every week, including any from an earlier interrupted run, was built from
+    let mut weeks = checkpoint.weeks().to_vec();
+    weeks.sort_by(|a, b| a.date.cmp(&b.date));
+    let reportpath = report::report_path(&outpath);
+    report::write_report(&reportpath, &report::RunReport { output: outpath.clone(), year, generated: nowstring(), config: config.clone(), weeks: weeks.clone() }).map_err(|e| CcmpError::output(&reportpath, e))?;
+    let mut outfile = netcdf::append(&outpath).map_err(|e| CcmpError::output(&outpath, e))?;
+    writer::write_provenance(&mut outfile, &weeks).map_err(|e| CcmpError::output(&outpath, e))?;
+    drop(outfile);
+    println!("wrote {} and {}", outpath, reportpath);
+
+    checkpoint.remove().map_err(|e| CcmpError::output(&checkpointpath, e))?;
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+// checking reduced files against the lattice they were meant to cover, and against their upstream files
+
+use ccmp_parse::config;
+use ccmp_parse::config::RunConfig;
+use ccmp_parse::config::WindowConfig;
+use ccmp_parse::error::CcmpError;
+use ccmp_parse::lattice;
+use ccmp_parse::proofread;
+use ccmp_parse::proofread::Coverage;
+use ccmp_parse::writer;
+
+use crate::cli::VerifyArgs;
+
+pub fn run(args: &VerifyArgs) -> Result<(), CcmpError> {
+    // check every year's file, and fail if any of them had problems
+
+    let (years, config) = args.resolve()?;
+    // reference weeks as days since EPOCH, to find them whatever their time of day is written as
+    let reference = lattice::days_since_epoch(&args.reference_week)?;
+    let mut starts = Vec::new();
+    for year in years.years() {
+        starts.extend(lattice::days_since_epoch(&config.window.lattice_in_year(year)?)?);
+    }
+    if let Some(r) = reference.iter().position(|day| !starts.contains(day)) {
+        return Err(CcmpError::Config(format!("--reference-week {} is not a window start in {}", args.reference_week[r], years)));
+    }
+    let coverage = if args.full {
+        Coverage::Full
+    } else {
+        Coverage::Samples { count: args.samples, seed: args.seed }
+    };
+    let mut bad = 0;
+    for year in years.years() {
+        let outpath = config::outfile_path(&config.output.path, year);
+        let timelattice = config.window.lattice_in_year(year)?;
+        let timesteps = lattice::days_since_epoch(&timelattice)?;
+        let (mut problems, aligned) = check_file(&outpath, &timelattice, &config.window)?;
+        // values can only be recomputed for the right windows once the time axis is known to match
+        if aligned && (args.full || args.samples > 0) {
+            let summary = proofread::check_file(&outpath, &timelattice, &config, &coverage)?;
+            problems.extend(summary.mismatches.iter().map(|m| m.to_string()));
+            let picked = if args.full { String::from("all values") } else { format!("seed {}", args.seed) };
+            println!("{}: {} of {} values recomputed from upstream disagree ({})", outpath, summary.mismatches.len(), summary.checked, picked);
+        }
+        for day in reference.iter() {
+            match timesteps.iter().position(|t| t == day) {
+                Some(week) if aligned => problems.extend(reference_week(&outpath, &timelattice, week, &config, args.worst)?),
+                _ => {}
+            }
+        }
+        for p in problems.iter() {
+            println!("{}: {}", outpath, p);
+        }
+        if problems.is_empty() {
+            println!("pass {}", outpath);
+        } else {
+            bad += 1;
+        }
+    }
+    if bad > 0 {
+        return Err(CcmpError::schema(&config.output.path, format!("{} of {} files failed verification", bad, years.years().count())));
+    }
+
+    Ok(())
+}
+
+fn reference_week(outpath: &str, timelattice: &[String], week: usize, config: &RunConfig, worst: usize) -> Result<Vec<String>, CcmpError> {
+    // print how far one week is from the reference reduction, variable by variable, and return its disagreements as problems
+
+    let mut problems = Vec::new();
+    println!("{}: reference reduction of window {}", outpath, timelattice[week]);
+    for errors in proofread::reference_week(outpath, timelattice, week, config, worst)? {
+        println!("    {}: max abs error {:e}, {} of {} cells disagree, {} counts disagree", errors.quantity, errors.max_abs_error, errors.mismatches, errors.cells, errors.count_mismatches);
+        for d in errors.worst.iter() {
+            println!("        {} {}", if d.agrees() { "within tolerance" } else { "DISAGREES" }, d);
+        }
+        if errors.mismatches > 0 {
+            problems.push(format!("window {} {} disagrees with the reference reduction in {} cells", timelattice[week], errors.quantity, errors.mismatches));
+        }
+    }
+
+    Ok(problems)
+}
+
+fn check_file(outpath: &str, timelattice: &[String], window: &WindowConfig) -> Result<(Vec<String>, bool), CcmpError> {
+    // compare a reduced file's window type, time coordinate and ndays against the expected lattice, and say whether
+    // the time coordinate matched; windows with no input at all are problems, short windows are only noted
+
+    let outfile = netcdf::open(outpath).map_err(|e| CcmpError::input(outpath, e))?;
+    let mut problems = Vec::new();
+
+    let kind = writer::window_kind(&outfile);
+    if kind != window.kind.to_string() {
+        problems.push(format!("holds {} windows, but {} windows are configured", kind, window.kind));
+        return Ok((problems, false));
+    }
+
+    let time = outfile.variable("time").ok_or_else(|| CcmpError::schema(outpath, "no time variable"))?.values::<i64, _>(..).map_err(|e| CcmpError::input(outpath, e))?;
+    let expected = lattice::days_since_epoch(timelattice)?;
+    if time != expected {
+        problems.push(format!("time coordinate has {} steps, expected {} starting {}", time.len(), expected.len(), timelattice.first().map(|s| s.as_str()).unwrap_or("nowhere")));
+        return Ok((problems, false));
+    }
+    let lengths: Vec<usize> = timelattice.iter().map(|d| Ok(window.window_days(d)?.len())).collect::<Result<_, CcmpError>>()?;
+    if let Some(bnds) = outfile.variable("time_bnds") {
+        let bnds = bnds.values::<i64, _>(..).map_err(|e| CcmpError::input(outpath, e))?;
+        let ends: Vec<i64> = expected.iter().zip(lengths.iter()).map(|(&t, &n)| t + n as i64).collect();
+        if bnds.chunks(2).map(|b| b[b.len() - 1]).collect::<Vec<i64>>() != ends {
+            problems.push(String::from("time_bnds do not end where the configured windows do"));
+        }
+    }
+
+    let ndays = outfile.variable("ndays").ok_or_else(|| CcmpError::schema(outpath, "no ndays variable"))?.values::<i32, _>(..).map_err(|e| CcmpError::input(outpath, e))?;
+    for ((date, n), length) in timelattice.iter().zip(ndays.iter()).zip(lengths.iter()) {
+        if *n <= 0 {
+            problems.push(format!("window {} has no input", date));
+        } else if (*n as usize) < *length {
+            println!("{}: window {} has only {} of {} days of input", outpath, date, n, length);
+        }
+    }
+
+    Ok((problems, true))
+}
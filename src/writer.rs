@@ -0,0 +1,681 @@
+//! Layout of the reduced netcdf output, written one week at a time.
+
+use crate::accumulator::direction;
+use crate::accumulator::mean;
+use crate::accumulator::steadiness;
+use crate::accumulator::vector_speed;
+use crate::accumulator::Accumulator;
+use crate::config::WindowConfig;
+use crate::lattice;
+use crate::lattice::WindowKind;
+use crate::report::WeekRecord;
+use crate::reduce::Grid;
+use crate::stress::Stress;
+
+// global attributes of the upstream CCMP files carried into reduced files, prefixed with upstream_
+const UPSTREAM_ATTRIBUTES: [&str; 12] = [
+    "title", "product_version", "institution", "source", "references", "license",
+    "summary", "keywords", "creator_name", "creator_url", "creator_email", "date_created",
+];
+
+// impementing a foreign trait on a forein struct //////////
+// per the advice in https://stackoverflow.com/questions/76277096/deconstructing-enums-in-rust/76277117#76277117
+
+struct Wrapper{
+    s: String
+}
+
+impl std::convert::TryFrom<netcdf::attribute::AttrValue> for Wrapper {
+    type Error = &'static str;
+
+    fn try_from(value: netcdf::attribute::AttrValue) -> Result<Self, Self::Error> {
+
+        if let netcdf::attribute::AttrValue::Str(v) = value {
+            Ok(Wrapper{s: v})
+        } else {
+            Err("nope")
+        }
+    }
+}
+////////////////////
+
+/// Chunk shape of the gridded output variables.
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Chunking {
+    /// netcdf library default chunk shapes
+    Default,
+    /// One time step per chunk, for reading whole maps
+    Map,
+    /// Every time step for a small patch of cells per chunk, for reading point time series; never deflated or shuffled
+    TimeSeries,
+    /// Explicit [time, latitude, longitude] chunk shape
+    Custom([usize; 3]),
+}
+
+impl std::str::FromStr for Chunking {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Chunking, String> {
+        // "default", "map", "timeseries", or a custom shape like "1,180,360"
+
+        match s {
+            "default" => Ok(Chunking::Default),
+            "map" => Ok(Chunking::Map),
+            "timeseries" => Ok(Chunking::TimeSeries),
+            _ => {
+                let dims: Vec<usize> = s.split(',').map(|d| d.trim().parse::<usize>()).collect::<Result<_, _>>().map_err(|_| format!("bad chunk shape {}", s))?;
+                match dims[..] {
+                    [t, y, x] if t > 0 && y > 0 && x > 0 => Ok(Chunking::Custom([t, y, x])),
+                    _ => Err(format!("chunk shape {} should be three positive sizes, time,latitude,longitude", s)),
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Chunking {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Chunking::Default => write!(f, "default"),
+            Chunking::Map => write!(f, "map"),
+            Chunking::TimeSeries => write!(f, "timeseries"),
+            Chunking::Custom([t, y, x]) => write!(f, "{},{},{}", t, y, x),
+        }
+    }
+}
+
+// config files spell chunking and precision the same way as the command line
+impl TryFrom<String> for Chunking {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Chunking, String> {
+        s.parse()
+    }
+}
+
+impl From<Chunking> for String {
+    fn from(c: Chunking) -> String {
+        c.to_string()
+    }
+}
+
+/// Storage type of the gridded means.
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Precision {
+    F64,
+    F32,
+    /// 16-bit integers unpacked by a scale_factor of 0.01, i.e. 0.01 m/s resolution
+    I16,
+}
+
+impl std::str::FromStr for Precision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Precision, String> {
+        match s {
+            "f64" => Ok(Precision::F64),
+            "f32" => Ok(Precision::F32),
+            "i16" => Ok(Precision::I16),
+            _ => Err(format!("precision {} should be one of f64, f32, i16", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for Precision {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Precision::F64 => write!(f, "f64"),
+            Precision::F32 => write!(f, "f32"),
+            Precision::I16 => write!(f, "i16"),
+        }
+    }
+}
+
+impl TryFrom<String> for Precision {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Precision, String> {
+        s.parse()
+    }
+}
+
+impl From<Precision> for String {
+    fn from(p: Precision) -> String {
+        p.to_string()
+    }
+}
+
+/// Storage options for the gridded output variables.
+pub struct Layout {
+    pub deflate: Option<i32>,
+    pub shuffle: bool,
+    pub chunking: Chunking,
+    /// Storage type of the means; nobs totals, directions and stresses use f32 unless this is f64, and counts are always i16.
+    pub precision: Precision,
+}
+
+impl Layout {
+    /// The [time, latitude, longitude] chunk shape for a file of ntime steps, or None for the library default.
+    pub fn chunk_shape(&self, ntime: usize, grid: &Grid) -> Option<[usize; 3]> {
+        match self.chunking {
+            Chunking::Default => None,
+            Chunking::Map => Some([1, grid.nlat, grid.nlon]),
+            Chunking::TimeSeries => Some([ntime.max(1), TIMESERIES_PATCH.min(grid.nlat), TIMESERIES_PATCH.min(grid.nlon)]),
+            Chunking::Custom([t, y, x]) => Some([t.min(ntime.max(1)), y.min(grid.nlat), x.min(grid.nlon)]),
+        }
+    }
+}
+
+// edge of the square patch of cells in each timeseries chunk; a year of f64 at 32x32 is about 430 kB
+const TIMESERIES_PATCH: usize = 32;
+
+fn add_gridded<'f, T: netcdf::NcPutGet>(outfile: &'f mut netcdf::MutableFile, name: &str, ntime: usize, layout: &Layout) -> Result<netcdf::VariableMut<'f>, netcdf::error::Error> {
+    // define a [time, latitude, longitude] variable with the requested storage layout
+
+    let grid = Grid {
+        nlat: outfile.dimension("latitude").ok_or("latitude dimension not defined")?.len(),
+        nlon: outfile.dimension("longitude").ok_or("longitude dimension not defined")?.len(),
+        nsteps: 1,
+    };
+    let mut v = outfile.add_variable::<T>(name, &["time", "latitude", "longitude"])?;
+    if let Some(shape) = layout.chunk_shape(ntime, &grid) {
+        v.chunking(&shape)?;
+    }
+    if let Some(level) = layout.deflate {
+        v.compression(level, layout.shuffle)?;
+    }
+
+    Ok(v)
+}
+
+// sentinel for cells with no valid upstream values, declared as _FillValue and missing_value
+const FILL: f64 = crate::accumulator::FILL;
+// packed counterparts for i16 means
+const PACKED_FILL: i16 = -32767;
+const PACKED_SCALE: f32 = 0.01;
+
+fn add_mean<'f>(outfile: &'f mut netcdf::MutableFile, name: &str, ntime: usize, layout: &Layout, precision: Precision) -> Result<netcdf::VariableMut<'f>, netcdf::error::Error> {
+    // define a gridded floating point quantity in the given precision, with its fill and packing attributes
+
+    let v = match precision {
+        Precision::F64 => {
+            let mut v = add_gridded::<f64>(outfile, name, ntime, layout)?;
+            v.set_fill_value(FILL)?;
+            v.add_attribute("missing_value", FILL)?;
+            v
+        }
+        Precision::F32 => {
+            let mut v = add_gridded::<f32>(outfile, name, ntime, layout)?;
+            v.set_fill_value(FILL as f32)?;
+            v.add_attribute("missing_value", FILL as f32)?;
+            v
+        }
+        Precision::I16 => {
+            let mut v = add_gridded::<i16>(outfile, name, ntime, layout)?;
+            v.set_fill_value(PACKED_FILL)?;
+            v.add_attribute("missing_value", PACKED_FILL)?;
+            v.add_attribute("scale_factor", PACKED_SCALE)?;
+            v.add_attribute("add_offset", 0.0f32)?;
+            v
+        }
+    };
+
+    Ok(v)
+}
+
+fn put_mean(outfile: &mut netcdf::MutableFile, var: &str, time: usize, values: &[f64], precision: Precision) -> Result<(), netcdf::error::Error> {
+    // write one time step of a gridded floating point quantity, converting FILL to the stored type's fill
+
+    match precision {
+        Precision::F64 => put_slab(outfile, var, time, values),
+        Precision::F32 => {
+            let narrow: Vec<f32> = values.iter().map(|&v| v as f32).collect();
+            put_slab(outfile, var, time, &narrow)
+        }
+        Precision::I16 => {
+            let packed: Vec<i16> = values.iter().map(|&v| {
+                if v == FILL {
+                    PACKED_FILL
+                } else {
+                    (v / PACKED_SCALE as f64).round().clamp(i16::MIN as f64 + 2.0, i16::MAX as f64) as i16
+                }
+            }).collect();
+            put_slab(outfile, var, time, &packed)
+        }
+    }
+}
+
+fn unpacked_precision(layout: &Layout) -> Precision {
+    // observation totals and directions up to 360 degrees outgrow i16 packing at 0.01 resolution,
+    // and stresses mostly fall below it, so all of them stay floating point
+
+    match layout.precision {
+        Precision::F64 => Precision::F64,
+        _ => Precision::F32,
+    }
+}
+
+/// Set up a new CF-1.8 netcdf file to hold a period's averages, with coordinates written
+/// and every gridded variable defined up front, so weeks can be filled in in any order.
+/// Bounds are the [start, end) of each window in days since EPOCH, one per time step.
+pub fn create(path: &str, upstream: &netcdf::File, bounds: &[[i64; 2]], window: &WindowConfig, stress: &Stress, created: &str, layout: &Layout) -> Result<netcdf::MutableFile, netcdf::error::Error> {
+    let mut outfile = netcdf::create(path)?;
+    outfile.add_attribute("Conventions", "CF-1.8")?;
+    outfile.add_attribute("title", format!("CCMP 10 m wind {} means", window.describe()))?;
+    outfile.add_attribute("date_created", created)?;
+    outfile.add_attribute("history", format!("{} ccmp_parse {}: {} means of upstream CCMP 6-hourly winds", created, env!("CARGO_PKG_VERSION"), window.describe()))?;
+    outfile.add_attribute("ccmp_parse_version", env!("CARGO_PKG_VERSION"))?;
+    // the kind of window is always recorded; a length only where every window has the same one
+    outfile.add_attribute("averaging_window_type", window.kind.to_string())?;
+    match window.kind {
+        WindowKind::Fixed | WindowKind::Daily => {
+            let days = window.max_days();
+            outfile.add_attribute("averaging_window", format!("{} days starting at each time value", days))?;
+            outfile.add_attribute("averaging_window_days", days as i32)?;
+        }
+        _ => {
+            outfile.add_attribute("averaging_window", format!("{} windows from each time value to its upper time_bnds", window.kind))?;
+        }
+    }
+    copy_upstream_attributes(&mut outfile, upstream)?;
+    // the grid follows the upstream coordinates, which reduce::check_upstream has matched to the configured grid
+    add_dimensions(&mut outfile, upstream, bounds.len())?;
+    write_coordinates(&mut outfile, upstream, bounds, false)?;
+
+    for (var, standard_name, long_name) in MEANS {
+        let mut v = add_mean(&mut outfile, var, bounds.len(), layout, layout.precision)?;
+        v.add_attribute("standard_name", standard_name)?;
+        v.add_attribute("long_name", long_name)?;
+        v.add_attribute("units", "m s-1")?;
+        v.add_attribute("cell_methods", "time: mean")?;
+        v.add_attribute("ancillary_variables", format!("{}_nobs", var))?;
+    }
+
+    // within-window variability of each mean, from the same 6-hourly values
+    for (var, standard_name, long_name) in MEANS {
+        for (suffix, method, description) in SPREAD {
+            let mut v = add_mean(&mut outfile, &format!("{}_{}", var, suffix), bounds.len(), layout, layout.precision)?;
+            v.add_attribute("standard_name", standard_name)?;
+            v.add_attribute("long_name", format!("{} 6-hourly {}", description, long_name.trim_start_matches("mean ")))?;
+            v.add_attribute("units", "m s-1")?;
+            v.add_attribute("cell_methods", format!("time: {}", method))?;
+            v.add_attribute("ancillary_variables", format!("{}_nobs", var))?;
+        }
+    }
+
+    // the mean wind vector, and how steady the wind was about it
+    for (var, standard_name, long_name, units) in VECTOR {
+        let precision = if var == "wind_dir" { unpacked_precision(layout) } else { layout.precision };
+        let mut v = add_mean(&mut outfile, var, bounds.len(), layout, precision)?;
+        if let Some(standard_name) = standard_name {
+            v.add_attribute("standard_name", standard_name)?;
+        }
+        v.add_attribute("long_name", long_name)?;
+        v.add_attribute("units", units)?;
+        v.add_attribute("ancillary_variables", "uwnd_nobs vwnd_nobs")?;
+    }
+
+    let mut nobs = add_mean(&mut outfile, "nobs", bounds.len(), layout, unpacked_precision(layout))?;
+    nobs.add_attribute("long_name", "total number of observations contributing to the upstream analysis")?;
+    nobs.add_attribute("units", "1")?;
+    nobs.add_attribute("cell_methods", "time: sum")?;
+
+    // stress is averaged from the stress of each 6-hourly wind, not computed from the mean wind
+    for (var, standard_name, long_name) in STRESS {
+        let mut v = add_mean(&mut outfile, var, bounds.len(), layout, unpacked_precision(layout))?;
+        v.add_attribute("standard_name", standard_name)?;
+        v.add_attribute("long_name", long_name)?;
+        v.add_attribute("units", "N m-2")?;
+        v.add_attribute("cell_methods", "time: mean")?;
+        v.add_attribute("comment", stress.describe())?;
+        v.add_attribute("ancillary_variables", "tau_nobs")?;
+    }
+
+    add_counts(&mut outfile, bounds.len(), layout)?;
+    let mut v = add_gridded::<i16>(&mut outfile, "tau_nobs", bounds.len(), layout)?;
+    v.add_attribute("long_name", "number of 6-hourly winds with both components whose stress is averaged into taux, tauy and tau")?;
+    v.add_attribute("units", "1")?;
+
+    Ok(outfile)
+}
+
+/// Reduced quantities, in the order of `Variables::names`; all but nobs are means with a `*_nobs` count.
+pub const QUANTITIES: [&str; 4] = ["uwnd", "vwnd", "ws", "nobs"];
+
+/// The window type recorded in a file's averaging_window_type attribute, like "monthly";
+/// files from before window types were recorded are all fixed windows.
+pub fn window_kind(f: &netcdf::File) -> String {
+    match f.attribute("averaging_window_type").map(|a| a.value()) {
+        Some(Ok(netcdf::attribute::AttrValue::Str(k))) => k,
+        _ => WindowKind::Fixed.to_string(),
+    }
+}
+
+// the gridded means of a reduced file, with their CF standard names and descriptions
+const MEANS: [(&str, &str, &str); 3] = [
+    ("uwnd", "eastward_wind", "mean eastward wind at 10 m"),
+    ("vwnd", "northward_wind", "mean northward wind at 10 m"),
+    ("ws", "wind_speed", "mean wind speed at 10 m"),
+];
+
+// the gridded mean stresses, with their CF standard names and descriptions
+const STRESS: [(&str, &str, &str); 3] = [
+    ("taux", "surface_downward_eastward_stress", "mean eastward surface wind stress"),
+    ("tauy", "surface_downward_northward_stress", "mean northward surface wind stress"),
+    ("tau", "magnitude_of_surface_downward_stress", "mean magnitude of surface wind stress"),
+];
+
+// quantities derived from the mean wind vector: name, CF standard name if any, description and units
+const VECTOR: [(&str, Option<&str>, &str, &str); 3] = [
+    ("vector_ws", None, "speed of the mean 10 m wind vector, sqrt(uwnd^2 + vwnd^2)", "m s-1"),
+    ("wind_dir", Some("wind_from_direction"), "direction the mean 10 m wind vector blows from, clockwise from north", "degree"),
+    ("steadiness", None, "steadiness of the 10 m wind, vector_ws / ws", "1"),
+];
+
+// the spread of each mean written beside it: variable suffix, CF cell method, and description
+const SPREAD: [(&str, &str, &str); 3] = [
+    ("std", "standard_deviation", "population standard deviation of"),
+    ("min", "minimum", "minimum"),
+    ("max", "maximum", "maximum"),
+];
+
+fn add_dimensions(outfile: &mut netcdf::MutableFile, source: &netcdf::File, ntime: usize) -> Result<(), netcdf::error::Error> {
+    // latitude and longitude sized as the source file's coordinates, and ntime time steps with their bounds
+
+    outfile.add_dimension("latitude", source.variable("latitude").ok_or("source latitude variable not found")?.len())?;
+    outfile.add_dimension("longitude", source.variable("longitude").ok_or("source longitude variable not found")?.len())?;
+    outfile.add_dimension("time", ntime)?;
+    outfile.add_dimension("nv", 2)?;
+
+    Ok(())
+}
+
+fn add_counts(outfile: &mut netcdf::MutableFile, ntime: usize, layout: &Layout) -> Result<(), netcdf::error::Error> {
+    // the *_nobs counts of each mean, and the days of input of each time step
+
+    // track how many non-fill-value observations each mean is calculated over
+    for (var, _, _) in MEANS {
+        let mut v = add_gridded::<i16>(outfile, &format!("{}_nobs", var), ntime, layout)?;
+        v.add_attribute("long_name", format!("number of 6-hourly values averaged into {}", var))?;
+        v.add_attribute("units", "1")?;
+    }
+
+    // how many daily files each week was built from, so degraded weeks can be filtered out
+    let mut ndays = outfile.add_variable::<i32>("ndays", &["time"])?;
+    ndays.add_attribute("long_name", "number of upstream daily files contributing to the time step")?;
+    ndays.add_attribute("units", "1")?;
+
+    Ok(())
+}
+
+fn copy_attributes(outfile: &mut netcdf::MutableFile, source: &netcdf::File, keep: impl Fn(&str) -> bool) -> Result<(), netcdf::error::Error> {
+    // carry over the global attributes of another output whose names pass keep
+
+    for attr in source.attributes() {
+        if keep(attr.name()) {
+            outfile.add_attribute(attr.name(), attr.value()?)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Set up a climatology file: one time step per window of the year, holding the mean over the baseline years
+/// of each quantity reduced for that window, and how many years had a value. The grid and upstream provenance
+/// follow `reduced`, one of the baseline's reduced files. Bounds are the CF climatology bounds of each step in
+/// days since EPOCH: the window's start in the first baseline year and its end in the last.
+pub fn create_climatology(path: &str, reduced: &netcdf::File, bounds: &[[i64; 2]], window: &WindowConfig, baseline: &str, created: &str, layout: &Layout) -> Result<netcdf::MutableFile, netcdf::error::Error> {
+    let mut outfile = netcdf::create(path)?;
+    outfile.add_attribute("Conventions", "CF-1.8")?;
+    outfile.add_attribute("title", format!("CCMP 10 m wind {} climatology, {}", window.describe(), baseline))?;
+    outfile.add_attribute("date_created", created)?;
+    outfile.add_attribute("history", format!("{} ccmp_parse {}: {} climatology of {} means", created, env!("CARGO_PKG_VERSION"), baseline, window.describe()))?;
+    outfile.add_attribute("ccmp_parse_version", env!("CARGO_PKG_VERSION"))?;
+    outfile.add_attribute("averaging_window_type", window.kind.to_string())?;
+    outfile.add_attribute("climatology_baseline", baseline)?;
+    copy_attributes(&mut outfile, reduced, |name| name.starts_with("upstream_"))?;
+    add_dimensions(&mut outfile, reduced, bounds.len())?;
+    write_coordinates(&mut outfile, reduced, bounds, true)?;
+
+    for (var, standard_name, long_name) in MEANS {
+        let mut v = add_mean(&mut outfile, var, bounds.len(), layout, layout.precision)?;
+        v.add_attribute("standard_name", standard_name)?;
+        v.add_attribute("long_name", format!("climatological {}", long_name))?;
+        v.add_attribute("units", "m s-1")?;
+        v.add_attribute("cell_methods", "time: mean within years time: mean over years")?;
+        v.add_attribute("ancillary_variables", format!("{}_nyears", var))?;
+    }
+    let mut nobs = add_mean(&mut outfile, "nobs", bounds.len(), layout, unpacked_precision(layout))?;
+    nobs.add_attribute("long_name", "mean over years of the total number of observations contributing to the upstream analysis")?;
+    nobs.add_attribute("units", "1")?;
+    nobs.add_attribute("cell_methods", "time: sum within years time: mean over years")?;
+
+    for var in QUANTITIES {
+        let mut v = add_gridded::<i16>(&mut outfile, &format!("{}_nyears", var), bounds.len(), layout)?;
+        v.add_attribute("long_name", format!("number of baseline years averaged into {}", var))?;
+        v.add_attribute("units", "1")?;
+    }
+
+    Ok(outfile)
+}
+
+/// Write one window of the year's climatological means and year counts, in uwnd, vwnd, ws, nobs order;
+/// means are FILL where no year had a value.
+pub fn write_climatology(outfile: &mut netcdf::MutableFile, time: usize, means: &[Vec<f64>], nyears: &[Vec<i32>], layout: &Layout) -> Result<(), netcdf::error::Error> {
+    for (q, var) in QUANTITIES.iter().enumerate() {
+        let precision = if *var == "nobs" { unpacked_precision(layout) } else { layout.precision };
+        put_mean(outfile, var, time, &means[q], precision)?;
+        put_counts(outfile, &format!("{}_nyears", var), time, &nyears[q])?;
+    }
+
+    Ok(())
+}
+
+/// Set up an anomaly file for one reduced file: the same grid, time axis, counts and metadata,
+/// with each mean replaced by its difference from the climatology.
+pub fn create_anomalies(path: &str, reduced: &netcdf::File, reducedpath: &str, climatologypath: &str, baseline: &str, created: &str, layout: &Layout) -> Result<netcdf::MutableFile, netcdf::error::Error> {
+    let mut outfile = netcdf::create(path)?;
+    copy_attributes(&mut outfile, reduced, |name| !matches!(name, "title" | "date_created" | "history" | "ccmp_parse_version"))?;
+    let title = reduced.attribute("title").map(|a| a.value()).transpose()?.and_then(|v| Wrapper::try_from(v).ok()).map(|w| w.s).unwrap_or_else(|| String::from("CCMP 10 m wind means"));
+    outfile.add_attribute("title", format!("{}, anomalies from the {} climatology", title, baseline))?;
+    outfile.add_attribute("date_created", created)?;
+    outfile.add_attribute("history", format!("{} ccmp_parse {}: {} minus {}", created, env!("CARGO_PKG_VERSION"), reducedpath, climatologypath))?;
+    outfile.add_attribute("ccmp_parse_version", env!("CARGO_PKG_VERSION"))?;
+    outfile.add_attribute("climatology_baseline", baseline)?;
+    outfile.add_attribute("climatology_file", climatologypath)?;
+
+    let ntime = reduced.variable("time").ok_or("reduced time variable not found")?.len();
+    let bounds = reduced.variable("time_bnds").ok_or("reduced time_bnds variable not found")?.values::<i64, _>(..)?;
+    let bounds: Vec<[i64; 2]> = bounds.chunks_exact(2).map(|b| [b[0], b[1]]).collect();
+    add_dimensions(&mut outfile, reduced, ntime)?;
+    write_coordinates(&mut outfile, reduced, &bounds, false)?;
+
+    for (var, _, long_name) in MEANS {
+        let mut v = add_mean(&mut outfile, var, ntime, layout, layout.precision)?;
+        v.add_attribute("long_name", format!("anomaly of {} from the {} climatology", long_name, baseline))?;
+        v.add_attribute("units", "m s-1")?;
+        v.add_attribute("cell_methods", "time: mean")?;
+        v.add_attribute("ancillary_variables", format!("{}_nobs", var))?;
+    }
+    add_counts(&mut outfile, ntime, layout)?;
+
+    Ok(outfile)
+}
+
+/// Write one time step's anomalies, in uwnd, vwnd, ws order, with the counts and input days of the means they came from.
+pub fn write_anomalies(outfile: &mut netcdf::MutableFile, time: usize, anomalies: &[Vec<f64>], counts: &[Vec<i32>], ndays: i32, layout: &Layout) -> Result<(), netcdf::error::Error> {
+    for (q, (var, _, _)) in MEANS.iter().enumerate() {
+        put_mean(outfile, var, time, &anomalies[q], layout.precision)?;
+        put_counts(outfile, &format!("{}_nobs", var), time, &counts[q])?;
+    }
+    outfile.variable_mut("ndays").ok_or("output variable ndays not defined")?.put_value(ndays, [time])?;
+
+    Ok(())
+}
+
+fn copy_upstream_attributes(outfile: &mut netcdf::MutableFile, upstream: &netcdf::File) -> Result<(), netcdf::error::Error> {
+    // carry the upstream product's provenance, where present as string attributes
+
+    for name in UPSTREAM_ATTRIBUTES {
+        let value = match upstream.attribute(name) {
+            Some(attr) => attr.value()?,
+            None => continue,
+        };
+        if let Ok(w) = Wrapper::try_from(value) {
+            outfile.add_attribute(&format!("upstream_{}", name), w.s)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The complete run definition, as TOML, so the file can be rebuilt from it alone.
+pub fn write_run_config(outfile: &mut netcdf::MutableFile, config: &str) -> Result<(), netcdf::error::Error> {
+    outfile.add_attribute("run_config", config)?;
+
+    Ok(())
+}
+
+/// The run definition recorded by write_run_config, if the file has one.
+pub fn read_run_config(f: &netcdf::File) -> Option<String> {
+    let value = f.attribute("run_config")?.value().ok()?;
+    Wrapper::try_from(value).ok().map(|w| w.s)
+}
+
+/// Record exactly which upstream files contributed to the finished output, one path per line.
+pub fn write_provenance(outfile: &mut netcdf::MutableFile, weeks: &[WeekRecord]) -> Result<(), netcdf::error::Error> {
+    let files: Vec<&str> = weeks.iter().flat_map(|w| w.inputs.iter()).map(|f| f.as_str()).collect();
+    outfile.add_attribute("input_files", files.join("\n"))?;
+
+    Ok(())
+}
+
+fn put_slab<T: netcdf::NcPutGet>(outfile: &mut netcdf::MutableFile, var: &str, time: usize, values: &[T]) -> Result<(), netcdf::error::Error> {
+    let mut v = outfile.variable_mut(var).ok_or_else(|| format!("output variable {} not defined", var))?;
+    v.put_values(values, (time, .., ..))
+}
+
+fn put_counts(outfile: &mut netcdf::MutableFile, var: &str, time: usize, counts: &[i32]) -> Result<(), netcdf::error::Error> {
+    // counts are stored as i16; RunConfig::check keeps windows short enough for every count to fit
+
+    let narrow: Vec<i16> = counts.iter().map(|&c| i16::try_from(c)).collect::<Result<_, _>>()
+        .map_err(|_| format!("{} has a count past the i16 limit of {}", var, i16::MAX))?;
+    put_slab(outfile, var, time, &narrow)
+}
+
+/// Write one week's means, their spread, the mean vector's speed and direction, mean stresses, counts and input day count at the given time index.
+pub fn write_week(outfile: &mut netcdf::MutableFile, time: usize, acc: &Accumulator, ndays: usize, layout: &Layout) -> Result<(), netcdf::error::Error> {
+    let (u, v, ws) = (mean(&acc.uwnd, &acc.count_uwnd), mean(&acc.vwnd, &acc.count_vwnd), mean(&acc.ws, &acc.count_ws));
+    let vector = vector_speed(&u, &v);
+    put_mean(outfile, "uwnd", time, &u, layout.precision)?;
+    put_mean(outfile, "vwnd", time, &v, layout.precision)?;
+    put_mean(outfile, "ws", time, &ws, layout.precision)?;
+    put_mean(outfile, "vector_ws", time, &vector, layout.precision)?;
+    put_mean(outfile, "wind_dir", time, &direction(&u, &v), unpacked_precision(layout))?;
+    put_mean(outfile, "steadiness", time, &steadiness(&vector, &ws), layout.precision)?;
+    for (var, spread, count) in [("uwnd", &acc.spread_uwnd, &acc.count_uwnd), ("vwnd", &acc.spread_vwnd, &acc.count_vwnd), ("ws", &acc.spread_ws, &acc.count_ws)] {
+        put_mean(outfile, &format!("{}_std", var), time, &spread.std(count), layout.precision)?;
+        put_mean(outfile, &format!("{}_min", var), time, &spread.minimum(), layout.precision)?;
+        put_mean(outfile, &format!("{}_max", var), time, &spread.maximum(), layout.precision)?;
+    }
+    put_mean(outfile, "nobs", time, &acc.nobs, unpacked_precision(layout))?;
+    put_mean(outfile, "taux", time, &mean(&acc.taux, &acc.count_tau), unpacked_precision(layout))?;
+    put_mean(outfile, "tauy", time, &mean(&acc.tauy, &acc.count_tau), unpacked_precision(layout))?;
+    put_mean(outfile, "tau", time, &mean(&acc.tau, &acc.count_tau), unpacked_precision(layout))?;
+    put_counts(outfile, "uwnd_nobs", time, &acc.count_uwnd)?;
+    put_counts(outfile, "vwnd_nobs", time, &acc.count_vwnd)?;
+    put_counts(outfile, "ws_nobs", time, &acc.count_ws)?;
+    put_counts(outfile, "tau_nobs", time, &acc.count_tau)?;
+    outfile.variable_mut("ndays").ok_or("output variable ndays not defined")?.put_value(ndays as i32, [time])?;
+
+    Ok(())
+}
+
+/// How the stored values of a gridded output variable map back to physical values,
+/// per its _FillValue, scale_factor and add_offset attributes.
+pub struct Unpacking {
+    pub fill: Option<f64>,
+    pub scale: f64,
+    pub offset: f64,
+}
+
+impl Unpacking {
+    /// Read the packing attributes of a variable; absent attributes leave values as stored.
+    pub fn of(var: &netcdf::Variable) -> Unpacking {
+        Unpacking {
+            fill: attribute_f64(var, "_FillValue"),
+            scale: attribute_f64(var, "scale_factor").unwrap_or(1.0),
+            offset: attribute_f64(var, "add_offset").unwrap_or(0.0),
+        }
+    }
+
+    /// A stored value in physical units, or None if it is the fill value.
+    pub fn unpack(&self, x: f64) -> Option<f64> {
+        if Some(x) == self.fill {
+            None
+        } else {
+            Some(x*self.scale + self.offset)
+        }
+    }
+
+    /// Largest difference rounding to the stored type can make; zero for unpacked floating point.
+    pub fn rounding(&self) -> f64 {
+        if self.scale != 1.0 {
+            self.scale.abs() / 2.0
+        } else {
+            0.0
+        }
+    }
+}
+
+fn attribute_f64(var: &netcdf::Variable, name: &str) -> Option<f64> {
+    // numeric attribute value, whatever its stored type
+
+    match var.attribute(name)?.value().ok()? {
+        netcdf::attribute::AttrValue::Short(v) => Some(v as f64),
+        netcdf::attribute::AttrValue::Int(v) => Some(v as f64),
+        netcdf::attribute::AttrValue::Float(v) => Some(v as f64),
+        netcdf::attribute::AttrValue::Double(v) => Some(v),
+        _ => None,
+    }
+}
+
+fn write_coordinates(outfile: &mut netcdf::MutableFile, upstream: &netcdf::File, bounds: &[[i64; 2]], climatology: bool) -> Result<(), netcdf::error::Error> {
+    // propagate latitude and longitude from an upstream file, and record the time coordinate with its averaging bounds,
+    // or for a climatology its climatology bounds
+
+    let lats = upstream.variable("latitude").ok_or("upstream latitude variable not found")?.values::<f64, _>(..)?;
+    let mut latvals = outfile.add_variable::<f64>("latitude", &["latitude"])?;
+    latvals.add_attribute("standard_name", "latitude")?;
+    latvals.add_attribute("long_name", "latitude")?;
+    latvals.add_attribute("units", "degrees_north")?;
+    latvals.add_attribute("axis", "Y")?;
+    latvals.put_values(&lats, ..)?;
+
+    let lons = upstream.variable("longitude").ok_or("upstream longitude variable not found")?.values::<f64, _>(..)?;
+    let mut lonvals = outfile.add_variable::<f64>("longitude", &["longitude"])?;
+    lonvals.add_attribute("standard_name", "longitude")?;
+    lonvals.add_attribute("long_name", "longitude")?;
+    lonvals.add_attribute("units", "degrees_east")?;
+    lonvals.add_attribute("axis", "X")?;
+    lonvals.put_values(&lons, ..)?;
+
+    let mut time = outfile.add_variable::<i64>("time", &["time"])?;
+    time.add_attribute("standard_name", "time")?;
+    time.add_attribute("long_name", if climatology { "start of averaging window in the first baseline year" } else { "start of averaging window" })?;
+    time.add_attribute("units", lattice::TIME_UNITS)?;
+    time.add_attribute("calendar", "standard")?;
+    time.add_attribute("axis", "T")?;
+    let bounds_name = if climatology { "climatology_bounds" } else { "time_bnds" };
+    time.add_attribute(if climatology { "climatology" } else { "bounds" }, bounds_name)?;
+    time.put_values(&bounds.iter().map(|b| b[0]).collect::<Vec<i64>>(), ..)?;
+
+    let bounds: Vec<i64> = bounds.iter().flatten().copied().collect();
+    let mut time_bnds = outfile.add_variable::<i64>(bounds_name, &["time", "nv"])?;
+    time_bnds.add_attribute("units", lattice::TIME_UNITS)?;
+    time_bnds.put_values(&bounds, (.., ..))?;
+
+    Ok(())
+}
@@ -0,0 +1,377 @@
+// end-to-end reductions of synthetic upstream files, checked cell by cell against means worked out by hand
+
+mod support;
+
+use ccmp_parse::climatology;
+use ccmp_parse::checkpoint::checkpoint_path;
+use ccmp_parse::config::outfile_path;
+use ccmp_parse::config::RunConfig;
+use ccmp_parse::config::YearRange;
+use ccmp_parse::error::CcmpError;
+use ccmp_parse::lattice::WindowKind;
+use ccmp_parse::proofread;
+use ccmp_parse::reduce::Grid;
+use ccmp_parse::run::reduce_year;
+use ccmp_parse::writer::Precision;
+use ccmp_parse::writer::QUANTITIES;
+
+use support::*;
+
+fn assert_week(path: &str, week: usize, days: &[chrono::NaiveDate]) {
+    // every quantity, count and spread of every cell of one week, as expected from those days
+
+    for cell in 0..GRID.ncells() {
+        for (q, name) in QUANTITIES.iter().enumerate() {
+            let (mean, n) = expected(q, days, cell);
+            assert_eq!(stored(path, name, week, cell), mean, "{} at cell {}", name, cell);
+            if *name != "nobs" {
+                assert_eq!(stored(path, &format!("{}_nobs", name), week, cell), Some(n as f64), "{}_nobs at cell {}", name, cell);
+                assert_spread(path, name, week, cell, q, days, mean);
+            }
+        }
+        assert_vector(path, week, cell, days);
+        assert_stress(path, week, cell, days);
+    }
+}
+
+fn assert_stress(path: &str, week: usize, cell: usize, days: &[chrono::NaiveDate]) {
+    // mean stress of the 6-hourly winds with both components, under the default Large & Yeager drag and air density 1.22
+
+    let mut sums = [0.0; 3];
+    let mut n = 0;
+    for &d in days {
+        for s in 0..GRID.nsteps {
+            let (u, v) = (value(0, d, cell, s), value(1, d, cell, s));
+            if u.is_nan() || v.is_nan() {
+                continue;
+            }
+            let speed = (u*u + v*v).sqrt();
+            let w = speed.max(0.5);
+            let cd = (2.7/w + 0.142 + w/13.09 - 3.14807e-10*w.powi(6))*1e-3;
+            let (taux, tauy) = (1.22*cd*speed*u, 1.22*cd*speed*v);
+            sums[0] += taux;
+            sums[1] += tauy;
+            sums[2] += (taux*taux + tauy*tauy).sqrt();
+            n += 1;
+        }
+    }
+    assert_eq!(stored(path, "tau_nobs", week, cell), Some(n as f64), "tau_nobs at cell {}", cell);
+    for (name, sum) in ["taux", "tauy", "tau"].iter().zip(sums) {
+        match (stored(path, name, week, cell), n) {
+            (Some(x), n) if n > 0 => assert!((x - sum / n as f64).abs() < 1e-12, "{} at cell {}: {} rather than {}", name, cell, x, sum / n as f64),
+            (x, _) => assert_eq!(x, None, "{} at cell {}", name, cell),
+        }
+    }
+}
+
+fn assert_vector(path: &str, week: usize, cell: usize, days: &[chrono::NaiveDate]) {
+    // the mean wind vector's speed, direction and steadiness, from the expected means
+
+    let (u, v, ws) = (expected(0, days, cell).0, expected(1, days, cell).0, expected(2, days, cell).0);
+    let speed = u.zip(v).map(|(u, v)| (u*u + v*v).sqrt());
+    let direction = u.zip(v).filter(|&(u, v)| u != 0.0 || v != 0.0).map(|(u, v)| (270.0 - v.atan2(u).to_degrees()).rem_euclid(360.0));
+    let steadiness = speed.zip(ws).filter(|&(_, s)| s > 0.0).map(|(vs, s)| vs / s);
+    for (name, expected) in [("vector_ws", speed), ("wind_dir", direction), ("steadiness", steadiness)] {
+        match (stored(path, name, week, cell), expected) {
+            (Some(x), Some(e)) => assert!((x - e).abs() < 1e-9, "{} at cell {}: {} rather than {}", name, cell, x, e),
+            (x, e) => assert_eq!(x, e, "{} at cell {}", name, cell),
+        }
+    }
+}
+
+fn assert_spread(path: &str, name: &str, week: usize, cell: usize, q: usize, days: &[chrono::NaiveDate], mean: Option<f64>) {
+    // min and max exactly, and the standard deviation to rounding, against a two-pass computation
+
+    let present: Vec<f64> = days.iter().flat_map(|&d| (0..GRID.nsteps).map(move |s| value(q, d, cell, s))).filter(|x| !x.is_nan()).collect();
+    let min = present.iter().copied().reduce(f64::min);
+    let max = present.iter().copied().reduce(f64::max);
+    assert_eq!(stored(path, &format!("{}_min", name), week, cell), min, "{}_min at cell {}", name, cell);
+    assert_eq!(stored(path, &format!("{}_max", name), week, cell), max, "{}_max at cell {}", name, cell);
+    let std = mean.map(|m| (present.iter().map(|x| (x - m).powi(2)).sum::<f64>() / present.len() as f64).sqrt());
+    match (stored(path, &format!("{}_std", name), week, cell), std) {
+        (Some(s), Some(e)) => assert!((s - e).abs() < 1e-12, "{}_std at cell {}: {} rather than {}", name, cell, s, e),
+        (s, e) => assert_eq!(s, e, "{}_std at cell {}", name, cell),
+    }
+}
+
+fn ndays(path: &str, week: usize) -> i32 {
+    netcdf::open(path).unwrap().variable("ndays").unwrap().value::<i32, _>([week]).unwrap()
+}
+
+fn interrupted_run(scratch: &Scratch) -> (RunConfig, String) {
+    // three weeks of input from 1993-01-03, with the run stopped by an unreadable day in the third,
+    // leaving the first two weeks written and checkpointed; the bad day is then replaced for the rerun
+
+    let root = scratch.path("ccmp");
+    write_days(&root, date(1993, 1, 3), 21);
+    let broken = write_day(&root, date(1993, 1, 19));
+    netcdf::create(&broken).unwrap();
+    let config = config(scratch, &[&root]);
+    assert!(matches!(reduce_year(1993, &config), Err(CcmpError::Schema { .. })));
+    let path = outfile_path(&config.output.path, 1993);
+    assert_eq!(std::fs::read_to_string(checkpoint_path(&path)).unwrap().lines().count(), 2);
+    write_day(&root, date(1993, 1, 19));
+
+    (config, path)
+}
+
+#[test]
+fn resume_needs_the_same_run_definition() {
+    let scratch = Scratch::new("resume_config");
+    let (mut config, path) = interrupted_run(&scratch);
+
+    config.output.precision = Precision::F32;
+    assert!(matches!(reduce_year(1993, &config), Err(CcmpError::Config(_))));
+
+    // a different thread count writes the same output, so may finish the run
+    config.output.precision = Precision::F64;
+    config.output.threads = 2;
+    reduce_year(1993, &config).unwrap();
+    assert_week(&path, 0, &days(date(1993, 1, 3), 7));
+    assert_week(&path, 1, &days(date(1993, 1, 10), 7));
+    assert_week(&path, 2, &days(date(1993, 1, 17), 7));
+    assert!(!std::path::Path::new(&checkpoint_path(&path)).exists());
+}
+
+#[test]
+fn resume_survives_a_torn_checkpoint() {
+    // a run stopped while recording its second week leaves half a line behind
+    let scratch = Scratch::new("resume_torn");
+    let (mut config, resumed) = interrupted_run(&scratch);
+    let checkpoint = checkpoint_path(&resumed);
+    let text = std::fs::read_to_string(&checkpoint).unwrap();
+    std::fs::write(&checkpoint, &text[..text.len() - 20]).unwrap();
+
+    reduce_year(1993, &config).unwrap();
+    config.output.path = scratch.path("clean_{year}.nc");
+    reduce_year(1993, &config).unwrap();
+    assert_same_output(&resumed, &outfile_path(&config.output.path, 1993));
+}
+
+#[test]
+fn resume_starts_over_from_a_torn_output() {
+    // a run stopped while writing a week can leave an output that no longer opens
+    let scratch = Scratch::new("resume_torn_output");
+    let (mut config, resumed) = interrupted_run(&scratch);
+    let bytes = std::fs::read(&resumed).unwrap();
+    std::fs::write(&resumed, &bytes[..bytes.len() / 2]).unwrap();
+
+    reduce_year(1993, &config).unwrap();
+    config.output.path = scratch.path("clean_{year}.nc");
+    reduce_year(1993, &config).unwrap();
+    assert_same_output(&resumed, &outfile_path(&config.output.path, 1993));
+}
+
+fn assert_same_output(a: &str, b: &str) {
+    // every gridded variable and count of two reduced files, bit for bit
+
+    let (a, b) = (netcdf::open(a).unwrap(), netcdf::open(b).unwrap());
+    for name in QUANTITIES.iter().map(|q| q.to_string()).chain(["uwnd_nobs", "vwnd_nobs", "ws_nobs", "uwnd_std", "vwnd_std", "ws_std", "taux", "tauy", "tau", "tau_nobs", "ndays"].map(String::from)) {
+        let x = a.variable(&name).unwrap().values::<f64, _>(..).unwrap();
+        let y = b.variable(&name).unwrap().values::<f64, _>(..).unwrap();
+        assert_eq!(x.iter().map(|v| v.to_bits()).collect::<Vec<_>>(), y.iter().map(|v| v.to_bits()).collect::<Vec<_>>(), "{}", name);
+    }
+}
+
+#[test]
+fn full_week_means_and_counts_are_exact() {
+    let scratch = Scratch::new("full_week");
+    let root = scratch.path("ccmp");
+    let start = date(1993, 1, 3);
+    write_days(&root, start, 7);
+    let config = config(&scratch, &[&root]);
+
+    reduce_year(1993, &config).unwrap();
+    let path = outfile_path(&config.output.path, 1993);
+    assert_week(&path, 0, &days(start, 7));
+    assert_eq!(ndays(&path, 0), 7);
+    // the land cell has no values at all, so it is left as fill with nothing counted
+    assert_eq!(stored(&path, "uwnd", 0, LAND_CELL), None);
+    assert_eq!(stored(&path, "uwnd_nobs", 0, LAND_CELL), Some(0.0));
+    // a week with no upstream files at all is fill throughout
+    assert_eq!(ndays(&path, 1), 0);
+    assert_week(&path, 1, &[]);
+}
+
+#[test]
+fn missing_days_are_left_out() {
+    let scratch = Scratch::new("missing_days");
+    let root = scratch.path("ccmp");
+    let start = date(1993, 3, 7);
+    let present: Vec<_> = days(start, 7).into_iter().filter(|&d| d != date(1993, 3, 10)).collect();
+    for &d in present.iter() {
+        write_day(&root, d);
+    }
+    let mut config = config(&scratch, &[&root]);
+
+    reduce_year(1993, &config).unwrap();
+    let path = outfile_path(&config.output.path, 1993);
+    let week = week_index(start);
+    assert_week(&path, week, &present);
+    assert_eq!(ndays(&path, week), 6);
+
+    config.output.path = scratch.path("strict_{year}.nc");
+    config.output.require_full_weeks = true;
+    assert!(matches!(reduce_year(1993, &config), Err(CcmpError::Input { .. })));
+}
+
+#[test]
+fn a_later_file_on_another_grid_is_refused() {
+    // only the first upstream file found is checked up front; the rest must be checked as they are read
+    let scratch = Scratch::new("later_grid");
+    let root = scratch.path("ccmp");
+    let start = date(1993, 3, 7);
+    write_days(&root, start, 6);
+    for (name, grid) in [("longer", Grid { nlat: GRID.nlat + 1, ..GRID }), ("shorter", Grid { nsteps: GRID.nsteps - 1, ..GRID })] {
+        write_day_on(&root, start + chrono::Duration::days(6), &grid, |_, _, _| 1.0);
+        let mut config = config(&scratch, &[&root]);
+        config.output.path = scratch.path(&format!("{}_{{year}}.nc", name));
+        assert!(matches!(reduce_year(1993, &config), Err(CcmpError::Schema { .. })), "{} file", name);
+    }
+}
+
+#[test]
+fn last_week_reads_into_next_year() {
+    // the last window of 1993 ends on 1994-01-01, found here under a second data root
+    let scratch = Scratch::new("year_crossing");
+    let (root1993, root1994) = (scratch.path("ccmp1993"), scratch.path("ccmp1994"));
+    let start = date(1993, 12, 26);
+    write_days(&root1993, start, 6);
+    write_day(&root1994, date(1994, 1, 1));
+    let config = config(&scratch, &[&root1993, &root1994]);
+
+    reduce_year(1993, &config).unwrap();
+    let path = outfile_path(&config.output.path, 1993);
+    let week = week_index(start);
+    assert_eq!(week, 51);
+    assert_week(&path, week, &days(start, 7));
+    assert_eq!(ndays(&path, week), 7);
+}
+
+#[test]
+fn calendar_months_are_reduced_whole() {
+    let scratch = Scratch::new("monthly");
+    let root = scratch.path("ccmp");
+    write_days(&root, date(1993, 1, 30), 4);
+    let mut config = config(&scratch, &[&root]);
+    config.window.kind = WindowKind::Monthly;
+
+    reduce_year(1993, &config).unwrap();
+    let path = outfile_path(&config.output.path, 1993);
+    assert_week(&path, 0, &days(date(1993, 1, 30), 2));
+    assert_week(&path, 1, &days(date(1993, 2, 1), 2));
+    assert_eq!((ndays(&path, 0), ndays(&path, 1), ndays(&path, 2)), (2, 2, 0));
+
+    let f = netcdf::open(&path).unwrap();
+    assert!(matches!(f.attribute("averaging_window_type").unwrap().value().unwrap(), netcdf::attribute::AttrValue::Str(k) if k == "monthly"));
+    let bounds = f.variable("time_bnds").unwrap().values::<i64, _>(..).unwrap();
+    let epoch = date(1993, 1, 1);
+    let month = |m: u32| (date(1993, m, 1) - epoch).num_days();
+    assert_eq!(bounds.len(), 24);
+    assert_eq!(&bounds[..4], &[month(1), month(2), month(2), month(3)]);
+    assert_eq!(bounds[23], (date(1994, 1, 1) - epoch).num_days());
+}
+
+#[test]
+fn climatology_and_anomalies_match_by_window_of_year() {
+    // the first window of 1993 starts on January 3 and that of 1994 on January 2; both are the climatology's first step
+    let scratch = Scratch::new("climatology");
+    let root = scratch.path("ccmp");
+    let (first1993, first1994) = (date(1993, 1, 3), date(1994, 1, 2));
+    write_days(&root, first1993, 7);
+    write_days(&root, first1994, 7);
+    let mut config = config(&scratch, &[&root]);
+    config.climatology.baseline = YearRange { first: 1993, last: 1994 };
+    config.climatology.path = scratch.path("climatology_{baseline}.nc");
+    config.climatology.anomalies = scratch.path("anomalies_{year}.nc");
+    reduce_year(1993, &config).unwrap();
+    reduce_year(1994, &config).unwrap();
+
+    let path = climatology::build_climatology(&config).unwrap();
+    assert_eq!(path, scratch.path("climatology_1993-1994.nc"));
+    let anomalies = climatology::write_anomalies(1993, &config).unwrap();
+    for cell in 0..GRID.ncells() {
+        for (q, name) in QUANTITIES.iter().enumerate() {
+            let present: Vec<f64> = [days(first1993, 7), days(first1994, 7)].iter().filter_map(|d| expected(q, d, cell).0).collect();
+            let normal = match present.len() {
+                0 => None,
+                n => Some(present.iter().fold(0.0, |s, x| s + x) / n as f64),
+            };
+            assert_eq!(stored(&path, name, 0, cell), normal, "{} at cell {}", name, cell);
+            assert_eq!(stored(&path, &format!("{}_nyears", name), 0, cell), Some(present.len() as f64), "{}_nyears at cell {}", name, cell);
+            // no baseline year has data for the second window
+            assert_eq!(stored(&path, name, 1, cell), None);
+            assert_eq!(stored(&path, &format!("{}_nyears", name), 1, cell), Some(0.0));
+
+            if *name != "nobs" {
+                let (mean, n) = expected(q, &days(first1993, 7), cell);
+                let anomaly = mean.zip(normal).map(|(m, c)| m - c);
+                assert_eq!(stored(&anomalies, name, 0, cell), anomaly, "{} anomaly at cell {}", name, cell);
+                assert_eq!(stored(&anomalies, &format!("{}_nobs", name), 0, cell), Some(n as f64));
+            }
+        }
+    }
+    assert_eq!(stored(&anomalies, "uwnd", 0, LAND_CELL), None);
+    assert_eq!(ndays(&anomalies, 0), 7);
+
+    let f = netcdf::open(&path).unwrap();
+    let bounds = f.variable("climatology_bounds").unwrap().values::<i64, _>(..).unwrap();
+    let epoch = date(1993, 1, 1);
+    assert_eq!(&bounds[..2], &[(first1993 - epoch).num_days(), (first1994 - epoch).num_days() + 7]);
+
+    // a climatology of windows of the same kind, but starting on other days, is refused
+    let mut shifted = config.clone();
+    shifted.window.anchor = String::from("1993-01-05T00:00:00.000Z");
+    shifted.output.path = scratch.path("shifted_{year}.nc");
+    reduce_year(1993, &shifted).unwrap();
+    assert!(matches!(climatology::write_anomalies(1993, &shifted), Err(CcmpError::Schema { .. })));
+
+    // files reduced to other windows are refused
+    config.window.kind = WindowKind::Monthly;
+    assert!(matches!(climatology::write_anomalies(1993, &config), Err(CcmpError::Schema { .. })));
+}
+
+#[test]
+fn packed_output_keeps_northerly_directions() {
+    // a steady wind from just west of north, about 350 degrees, beyond what i16 packing at 0.01 can hold
+    let scratch = Scratch::new("packed_direction");
+    let root = scratch.path("ccmp");
+    let start = date(1993, 1, 3);
+    for d in days(start, 7) {
+        write_day_with(&root, d, |q, _, _| [0.875, -5.0, 5.25, 1.0][q]);
+    }
+    let mut config = config(&scratch, &[&root]);
+    config.output.precision = Precision::I16;
+
+    reduce_year(1993, &config).unwrap();
+    let path = outfile_path(&config.output.path, 1993);
+    let expected = 360.0 - 0.875f64.atan2(5.0).to_degrees();
+    assert!(expected > 350.0);
+    for cell in 0..GRID.ncells() {
+        let stored = stored(&path, "wind_dir", 0, cell).unwrap();
+        assert!((stored - expected).abs() < 1e-4, "wind_dir at cell {}: {} rather than {}", cell, stored, expected);
+    }
+}
+
+#[test]
+fn threads_do_not_change_results() {
+    let scratch = Scratch::new("threads");
+    let root = scratch.path("ccmp");
+    write_days(&root, date(1993, 5, 30), 17);
+    let mut config = config(&scratch, &[&root]);
+
+    reduce_year(1993, &config).unwrap();
+    let serial = outfile_path(&config.output.path, 1993);
+    config.output.path = scratch.path("threaded_{year}.nc");
+    config.output.threads = 3;
+    reduce_year(1993, &config).unwrap();
+    let threaded = outfile_path(&config.output.path, 1993);
+
+    assert_same_output(&serial, &threaded);
+
+    // and the reduction agrees with verify's independent recomputation
+    let timelattice = config.window.lattice_in_year(1993).unwrap();
+    let summary = proofread::check_file(&threaded, &timelattice, &config, &proofread::Coverage::Full).unwrap();
+    assert_eq!(summary.checked, timelattice.len()*GRID.ncells()*QUANTITIES.len());
+    assert!(summary.mismatches.is_empty(), "{:?}", summary.mismatches.iter().map(|d| d.to_string()).collect::<Vec<_>>());
+}
@@ -0,0 +1,156 @@
+//! Synthetic CCMP-shaped upstream files for end-to-end tests: the dimensions, variables and
+//! attributes of the REMSS V3.0 daily files, on a grid small enough to check every cell.
+//! Values are multiples of 0.25, so every sum is exact and every mean is one rounding of it,
+//! however the reduction orders its additions.
+
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use std::path::PathBuf;
+
+use ccmp_parse::config::InputConfig;
+use ccmp_parse::config::RunConfig;
+use ccmp_parse::inputs;
+use ccmp_parse::lattice;
+use ccmp_parse::reduce::Grid;
+use ccmp_parse::writer::Unpacking;
+
+/// Two latitudes, three longitudes, four 6-hourly steps.
+pub const GRID: Grid = Grid { nlat: 2, nlon: 3, nsteps: 4 };
+
+/// A cell every upstream file leaves NaN, as over land.
+pub const LAND_CELL: usize = 4;
+
+/// A directory of its own for one test, removed when dropped.
+pub struct Scratch {
+    pub dir: PathBuf,
+}
+
+impl Scratch {
+    /// A fresh directory named for the test, so tests running in parallel keep apart.
+    pub fn new(name: &str) -> Scratch {
+        let dir = std::env::temp_dir().join(format!("ccmp_parse_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Scratch { dir }
+    }
+
+    /// A path below the directory.
+    pub fn path(&self, relative: &str) -> String {
+        self.dir.join(relative).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Calendar date from year, month and day.
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// The upstream value of quantity q (uwnd, vwnd, ws, nobs) at a cell and step of a day, or NaN.
+/// Roughly one value in seven is NaN besides LAND_CELL, in a pattern that moves from day to day.
+pub fn value(q: usize, day: NaiveDate, cell: usize, step: usize) -> f64 {
+    let k = day.num_days_from_ce() as i64*13 + cell as i64*3 + step as i64*5 + q as i64*11;
+    if cell == LAND_CELL || k % 7 == 3 {
+        return f64::NAN;
+    }
+    match q {
+        0 | 1 => (k % 97 - 48) as f64 * 0.25,
+        2 => (k % 61) as f64 * 0.25,
+        _ => (k % 4) as f64,
+    }
+}
+
+/// Write the upstream file for one day under root, at the path the default input filename gives it.
+pub fn write_day(root: &str, day: NaiveDate) -> String {
+    write_day_with(root, day, |q, cell, step| value(q, day, cell, step))
+}
+
+/// Write the upstream file for one day under root, with quantity q at each cell and step given by values.
+pub fn write_day_with(root: &str, day: NaiveDate, values: impl Fn(usize, usize, usize) -> f64) -> String {
+    write_day_on(root, day, &GRID, values)
+}
+
+/// Write the upstream file for one day under root on a grid other than the synthetic one.
+pub fn write_day_on(root: &str, day: NaiveDate, grid: &Grid, values: impl Fn(usize, usize, usize) -> f64) -> String {
+    let input = InputConfig { roots: vec![String::from(root)], ..InputConfig::default() };
+    let path = inputs::choosefile(&day.format("%Y%m%d").to_string(), &input);
+    std::fs::create_dir_all(std::path::Path::new(&path).parent().unwrap()).unwrap();
+
+    let mut f = netcdf::create(&path).unwrap();
+    f.add_attribute("title", "synthetic CCMP V3.0 daily file").unwrap();
+    f.add_attribute("product_version", "v03.0").unwrap();
+    f.add_dimension("latitude", grid.nlat).unwrap();
+    f.add_dimension("longitude", grid.nlon).unwrap();
+    f.add_dimension("time", grid.nsteps).unwrap();
+
+    let latitude: Vec<f32> = (0..grid.nlat).map(|i| -78.375 + 0.25*i as f32).collect();
+    let longitude: Vec<f32> = (0..grid.nlon).map(|i| 0.125 + 0.25*i as f32).collect();
+    let hours = (day - date(1987, 1, 1)).num_hours() as f64;
+    let time: Vec<f64> = (0..grid.nsteps).map(|s| hours + 6.0*s as f64).collect();
+    f.add_variable::<f32>("latitude", &["latitude"]).unwrap().put_values(&latitude, ..).unwrap();
+    f.add_variable::<f32>("longitude", &["longitude"]).unwrap().put_values(&longitude, ..).unwrap();
+    let mut t = f.add_variable::<f64>("time", &["time"]).unwrap();
+    t.add_attribute("units", "hours since 1987-01-01 00:00:00").unwrap();
+    t.put_values(&time, ..).unwrap();
+
+    for (q, name) in ["uwnd", "vwnd", "ws", "nobs"].iter().enumerate() {
+        let field: Vec<f32> = (0..grid.ncells()).flat_map(|cell| (0..grid.nsteps).map(move |s| (cell, s))).map(|(cell, s)| values(q, cell, s) as f32).collect();
+        f.add_variable::<f32>(name, &["latitude", "longitude", "time"]).unwrap().put_values(&field, ..).unwrap();
+    }
+
+    path
+}
+
+/// Write n consecutive days of upstream files under root, starting at first.
+pub fn write_days(root: &str, first: NaiveDate, n: i64) {
+    for i in 0..n {
+        write_day(root, first + Duration::days(i));
+    }
+}
+
+/// A run over the synthetic grid, reading from roots and writing below the scratch directory.
+pub fn config(scratch: &Scratch, roots: &[&str]) -> RunConfig {
+    let mut config = RunConfig { grid: GRID, ..RunConfig::default() };
+    config.input.roots = roots.iter().map(|r| String::from(*r)).collect();
+    config.output.path = scratch.path("means_{year}.nc");
+    config
+}
+
+/// The mean, or total for nobs, and count of quantity q at a cell over the given days, worked out
+/// directly from `value`; None where no value is present.
+pub fn expected(q: usize, days: &[NaiveDate], cell: usize) -> (Option<f64>, i32) {
+    let present: Vec<f64> = days.iter().flat_map(|&d| (0..GRID.nsteps).map(move |s| value(q, d, cell, s))).filter(|x| !x.is_nan()).collect();
+    let sum: f64 = present.iter().sum();
+    let n = present.len() as i32;
+    match (n, q) {
+        (0, _) => (None, 0),
+        (_, 3) => (Some(sum), n),
+        _ => (Some(sum / n as f64), n),
+    }
+}
+
+/// Days from first, inclusive, to first + n, exclusive.
+pub fn days(first: NaiveDate, n: i64) -> Vec<NaiveDate> {
+    (0..n).map(|i| first + Duration::days(i)).collect()
+}
+
+/// Index of the window starting at start in its year's reduced file, on the OISST lattice.
+pub fn week_index(start: NaiveDate) -> usize {
+    let weeks = lattice::lattice_in_year(lattice::OISST_ANCHOR, lattice::OISST_PERIOD, start.year()).unwrap();
+    let start = format!("{}T00:00:00.000Z", start);
+    weeks.iter().position(|w| *w == start).unwrap()
+}
+
+/// A reduced value at one week and cell, unpacked; None where it is the fill value.
+pub fn stored(path: &str, name: &str, week: usize, cell: usize) -> Option<f64> {
+    let f = netcdf::open(path).unwrap();
+    let v = f.variable(name).unwrap();
+    let x = v.value::<f64, _>([week, cell / GRID.nlon, cell % GRID.nlon]).unwrap();
+    Unpacking::of(&v).unpack(x)
+}